@@ -1,10 +1,19 @@
-//! Build script that auto-discovers tankas in content/
+//! Build script that auto-discovers tankas (and their translations) in content/, works out which are
+//! related, and embeds the fragments book.yml reads and the mirrored art manifest
 
 use std::env;
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::Path;
 
+#[path = "src/related.rs"]
+mod related;
+
+// Only the parsing is needed here; the rest is for the validator
+#[allow(dead_code)]
+#[path = "src/book/model.rs"]
+mod book;
+
 fn main() {
     let out_dir = env::var("OUT_DIR").unwrap();
     let dest_path = Path::new(&out_dir).join("tankas_generated.rs");
@@ -13,6 +22,8 @@ fn main() {
     // Scan content/ for yaml files
     let content_dir = Path::new("content");
     let mut tankas: Vec<String> = Vec::new();
+    // (tanka file, language, translation file)
+    let mut translations: Vec<(String, String, String)> = Vec::new();
 
     if content_dir.exists() {
         for entry in fs::read_dir(content_dir).unwrap().flatten() {
@@ -24,41 +35,133 @@ fn main() {
                 continue;
             }
 
-            tankas.push(name.to_string());
+            // `<name>.tanka.<lang>.yml` translates `<name>.tanka.yml` (same rule as model::translation_of)
+            let translated = name
+                .strip_suffix(".yml")
+                .and_then(|stem| stem.rsplit_once('.'))
+                .filter(|(stem, _)| stem.ends_with(".tanka"));
+            match translated {
+                Some((stem, lang)) => {
+                    translations.push((format!("{}.yml", stem), lang.to_string(), name.to_string()))
+                }
+                None => tankas.push(name.to_string()),
+            }
         }
     }
 
     // Sort for consistent ordering
     tankas.sort();
+    translations.sort();
 
     // Generate the code
-    writeln!(f, "/// Auto-generated list of tankas").unwrap();
-    writeln!(f, "fn all_tankas() -> Vec<TankaEntry> {{").unwrap();
-    writeln!(f, "    let files: Vec<(&str, &str)> = vec![").unwrap();
+    writeln!(f, "/// File name, YAML, and `(language, YAML)` for each translation").unwrap();
+    writeln!(f, "type ContentFile = (&'static str, &'static str, Vec<(&'static str, &'static str)>);").unwrap();
+    writeln!(f).unwrap();
+    writeln!(f, "/// Auto-generated list of tankas, in file name order").unwrap();
+    writeln!(f, "fn content_tankas() -> Vec<TankaEntry> {{").unwrap();
+    writeln!(f, "    let files: Vec<ContentFile> = vec![").unwrap();
 
     for filename in &tankas {
         writeln!(
             f,
-            "        (\"{}\", include_str!(concat!(env!(\"CARGO_MANIFEST_DIR\"), \"/content/{}\"))),",
+            "        (\"{}\", include_str!(concat!(env!(\"CARGO_MANIFEST_DIR\"), \"/content/{}\")), vec![",
             filename, filename
         ).unwrap();
+        // Translations of tankas that don't exist are left for the validator to report
+        for (_, lang, translation) in translations.iter().filter(|(of, _, _)| of == filename) {
+            writeln!(
+                f,
+                "            (\"{}\", include_str!(concat!(env!(\"CARGO_MANIFEST_DIR\"), \"/content/{}\"))),",
+                lang, translation
+            ).unwrap();
+        }
+        writeln!(f, "        ]),").unwrap();
     }
 
     writeln!(f, "    ];").unwrap();
     writeln!(f).unwrap();
     writeln!(f, "    files").unwrap();
     writeln!(f, "        .into_iter()").unwrap();
-    writeln!(f, "        .filter_map(|(filename, content)| {{").unwrap();
+    writeln!(f, "        .filter_map(|(filename, content, translations)| {{").unwrap();
     writeln!(f, "            let tanka = load_tanka(content)?;").unwrap();
     writeln!(f, "            Some(TankaEntry {{").unwrap();
     writeln!(f, "                slug: slugify(filename),").unwrap();
     writeln!(f, "                filename: filename.to_string(),").unwrap();
     writeln!(f, "                tanka,").unwrap();
+    writeln!(f, "                translations: translations").unwrap();
+    writeln!(f, "                    .into_iter()").unwrap();
+    writeln!(f, "                    .filter_map(|(lang, yaml)| Some((lang.to_string(), load_translation(yaml)?)))").unwrap();
+    writeln!(f, "                    .collect(),").unwrap();
     writeln!(f, "            }})").unwrap();
     writeln!(f, "        }})").unwrap();
     writeln!(f, "        .collect()").unwrap();
     writeln!(f, "}}").unwrap();
 
+    // "See also" for each tanka, scored once here rather than in every reader's browser
+    let features: Vec<related::Features> = tankas
+        .iter()
+        .map(|name| related::Features::from_yaml(&fs::read_to_string(content_dir.join(name)).unwrap_or_default()))
+        .collect();
+    let related = related::related(&features, related::RELATED_COUNT);
+
+    writeln!(f).unwrap();
+    writeln!(f, "/// Auto-generated: file names of the tankas most like each one, best first").unwrap();
+    writeln!(f, "fn related_files(filename: &str) -> &'static [&'static str] {{").unwrap();
+    writeln!(f, "    match filename {{").unwrap();
+    for (filename, similar) in tankas.iter().zip(&related) {
+        let similar: Vec<String> = similar.iter().map(|&j| format!("{:?}", tankas[j])).collect();
+        writeln!(f, "        {:?} => &[{}],", filename, similar.join(", ")).unwrap();
+    }
+    writeln!(f, "        _ => &[],").unwrap();
+    writeln!(f, "    }}").unwrap();
+    writeln!(f, "}}").unwrap();
+
+    // Fragments are drafts: only the ones book.yml reads get compiled in
+    // (a book.yml that doesn't parse compiles none in; the validator says why)
+    let book = book::Book::parse(&fs::read_to_string("book.yml").unwrap_or_default()).unwrap_or_default();
+    let mut fragments: Vec<&str> = book.fragments();
+    fragments.sort();
+    fragments.dedup();
+
+    writeln!(f).unwrap();
+    writeln!(f, "/// Auto-generated: name and text of each fragment in book.yml").unwrap();
+    writeln!(f, "pub fn all_fragments() -> Vec<(&'static str, &'static str)> {{").unwrap();
+    writeln!(f, "    vec![").unwrap();
+    for name in &fragments {
+        // Missing ones are left for the validator to report
+        if content_dir.join(format!("{}.fragmentedpoem", name)).exists() {
+            writeln!(
+                f,
+                "        ({:?}, include_str!(concat!(env!(\"CARGO_MANIFEST_DIR\"), {:?}))),",
+                name,
+                format!("/content/{}.fragmentedpoem", name)
+            ).unwrap();
+        }
+    }
+    writeln!(f, "    ]").unwrap();
+    writeln!(f, "}}").unwrap();
+
+    // Embed the mirrored album art manifest written by `fetch-assets`, if any
+    let art_dest = Path::new(&out_dir).join("art_generated.rs");
+    let mut art = File::create(&art_dest).unwrap();
+    let manifest = Path::new("assets/art/manifest.yml");
+
+    writeln!(art, "/// Auto-generated: contents of assets/art/manifest.yml").unwrap();
+    writeln!(art, "fn art_manifest_yaml() -> &'static str {{").unwrap();
+    if manifest.exists() {
+        writeln!(
+            art,
+            "    include_str!(concat!(env!(\"CARGO_MANIFEST_DIR\"), \"/assets/art/manifest.yml\"))"
+        ).unwrap();
+    } else {
+        writeln!(art, "    \"\"").unwrap();
+    }
+    writeln!(art, "}}").unwrap();
+
     // Tell Cargo to rerun if content/ changes
     println!("cargo:rerun-if-changed=content/");
+    println!("cargo:rerun-if-changed=book.yml");
+    println!("cargo:rerun-if-changed=src/related.rs");
+    println!("cargo:rerun-if-changed=src/book/model.rs");
+    println!("cargo:rerun-if-changed=assets/art/manifest.yml");
 }
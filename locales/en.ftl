@@ -0,0 +1,119 @@
+# UI strings, English. Every locale carries the ids in `MESSAGES` in
+# src/i18n.rs; `just validate` fails when one is missing.
+#
+# Shell prompts and commands (`$ ls -la`, `[ls]`) and the in-page shell's
+# output stay as they are: they're a terminal, not prose.
+
+## Age gate and the printed warning
+
+age-gate-warning = ⚠️ 21+ ONLY ⚠️
+age-gate-title = Digital K-Hole Version 0.1
+age-gate-about = This is a poetry book about my life that covers extreme themes.
+age-gate-audience = It may include content that is not suitable for young people or sensitive people.
+age-gate-exit = I am under 21
+age-gate-enter = I am 21 or older
+print-warning-title = 21+ ONLY
+print-warning-about = Digital K-Hole is a poetry book about my life that covers extreme themes.
+
+## Index
+
+site-description = A poetry book about growing up on the internet, told through tanka and music pairings.
+shell-open = [open shell]
+shell-close = [close shell]
+shell-hint = type `help` for commands, tab completes, arrows walk history
+shell-input-label = shell command
+soundtrack-prompt = $ download the soundtrack
+print-all = [print the whole book]
+toc-unsorted = unsorted
+tags-link = [tags]
+random-link = [random tanka]
+random-picking = picking a tanka you haven't just read
+
+## Content warnings
+
+cw-label = content warning:{" "}
+cw-hide = [hide]
+cw-show = [show anyway]
+cw-always-show = [always show]
+cw-always-hide = [always hide]
+cw-hide-some = [hide some]
+
+## Settings panels
+
+theme-system = [system]
+theme-dark = [dark]
+theme-light = [light]
+theme-high-contrast = [high contrast]
+theme-templeos = [templeos]
+locale-browser = [browser]
+
+## Listening session
+
+session-about = plays each tanka's pairing on its own and turns the page when the track ends
+session-pause = pause between tracks:{" "}
+session-start = [start listening session]
+session-stop-long = [stop listening session]
+session-stop = [stop]
+session-on = listening session: on
+session-up-next = listening session: up next in { $secs }s
+session-nothing = nothing to play here - moving on
+session-bandcamp = bandcamp can't start on its own: press play, then{" "}
+session-next = [next]
+
+## Reader data
+
+fav-add = [☆ favorite]
+fav-remove = [★ favorited]
+read-mark = [mark read]
+read-unmark = [mark unread]
+resume = resume:
+reader-about = favorites and what you've read stay in this browser; export them to read on another
+reader-counts = { $favorites } favorites, { $read }/{ $total } read
+reader-export = [export json]
+reader-import = [import json]
+reader-import-label = paste an export here
+reader-imported = imported
+reader-import-failed = import failed: { $error }
+
+## Music pairing
+
+art-alt = Album art
+qr-alt = Album QR code
+pairing-by = {" "}by{" "}
+pairing-from = {" "}from{" "}
+pairing-at = {" "}at{" "}
+volume-hint = suggested player volume: { $percent }%
+volume-meter = volume { $level }/{ $steps }
+links-also = also on:{" "}
+links-listen = listen on:{" "}
+private-notice = album is private - visit link to listen
+
+## Tanka page
+
+ja-vertical = [縦 日本語]
+ja-horizontal = [横 english only]
+see-also = see also:
+verse-link = link to this line
+share-copy = [copy quote + link]
+share-copied = copied
+share-failed = couldn't copy; the link is in the address bar
+nav-prev = < prev
+nav-next = next >
+nav-print = [print]
+not-found = tanka not found
+back-to-index = back to index
+fragment-not-found = fragment not found
+page-not-found = 404: no such page
+
+## Tags
+
+tags-title = tags
+tag-not-found = tag not found
+tag-empty = nothing tagged with this yet
+
+## Print views
+
+print-title = { $title } (print)
+print-all-title = the zine (print)
+print-back = < back
+print-button = [print]
@@ -0,0 +1,115 @@
+# UI strings, Japanese. Same message ids as en.ftl.
+
+## Age gate and the printed warning
+
+age-gate-warning = ⚠️ 21歳以上限定 ⚠️
+age-gate-title = Digital K-Hole バージョン 0.1
+age-gate-about = これは、過激なテーマを扱う、私の人生についての詩集です。
+age-gate-audience = 若い方や繊細な方には適さない内容が含まれている可能性があります。
+age-gate-exit = 21歳未満です
+age-gate-enter = 21歳以上です
+print-warning-title = 21歳以上限定
+print-warning-about = Digital K-Hole は、過激なテーマを扱う、私の人生についての詩集です。
+
+## Index
+
+site-description = インターネットで育ったことを、短歌と音楽のペアリングで綴る詩集。
+shell-open = [シェルを開く]
+shell-close = [シェルを閉じる]
+shell-hint = `help` でコマンド一覧、Tab で補完、矢印キーで履歴
+shell-input-label = シェルのコマンド
+soundtrack-prompt = $ サウンドトラックをダウンロード
+print-all = [本全体を印刷]
+toc-unsorted = 未分類
+tags-link = [タグ]
+random-link = [ランダムな短歌]
+random-picking = 最近読んでいない短歌を選んでいます
+
+## Content warnings
+
+cw-label = 注意：
+cw-hide = [隠す]
+cw-show = [それでも表示]
+cw-always-show = [常に表示]
+cw-always-hide = [常に隠す]
+cw-hide-some = [一部を隠す]
+
+## Settings panels
+
+theme-system = [システム]
+theme-dark = [ダーク]
+theme-light = [ライト]
+theme-high-contrast = [ハイコントラスト]
+theme-templeos = [templeos]
+locale-browser = [ブラウザ]
+
+## Listening session
+
+session-about = 各短歌のペアリングを自動で再生し、曲が終わるとページをめくります
+session-pause = 曲の間の休止：
+session-start = [リスニングセッションを開始]
+session-stop-long = [リスニングセッションを停止]
+session-stop = [停止]
+session-on = リスニングセッション：再生中
+session-up-next = リスニングセッション：{ $secs }秒後に次へ
+session-nothing = 再生できるものがありません - 次へ進みます
+session-bandcamp = bandcamp は自動で始められません：再生を押してから{" "}
+session-next = [次へ]
+
+## Reader data
+
+fav-add = [☆ お気に入り]
+fav-remove = [★ お気に入り済み]
+read-mark = [既読にする]
+read-unmark = [未読にする]
+resume = 続きから：
+reader-about = お気に入りと既読はこのブラウザに保存されます。書き出せば別のブラウザでも読めます
+reader-counts = お気に入り { $favorites }、既読 { $read }/{ $total }
+reader-export = [JSONを書き出す]
+reader-import = [JSONを読み込む]
+reader-import-label = 書き出したJSONをここに貼り付け
+reader-imported = 読み込みました
+reader-import-failed = 読み込めませんでした：{ $error }
+
+## Music pairing
+
+art-alt = アルバムアート
+qr-alt = アルバムのQRコード
+pairing-by = {" "}／{" "}
+pairing-from = {" "}／{" "}
+pairing-at = {" "}／ 音量{" "}
+volume-hint = おすすめのプレーヤー音量：{ $percent }%
+volume-meter = 音量 { $level }/{ $steps }
+links-also = 他の配信：
+links-listen = 聴く：
+private-notice = アルバムは非公開です - リンク先で聴いてください
+
+## Tanka page
+
+ja-vertical = [縦 日本語]
+ja-horizontal = [横 英語のみ]
+see-also = 関連：
+verse-link = この行へのリンク
+share-copy = [引用とリンクをコピー]
+share-copied = コピーしました
+share-failed = コピーできませんでした。リンクはアドレスバーにあります
+nav-prev = < 前へ
+nav-next = 次へ >
+nav-print = [印刷]
+not-found = 短歌が見つかりません
+back-to-index = 目次に戻る
+fragment-not-found = 断片が見つかりません
+page-not-found = 404：ページが見つかりません
+
+## Tags
+
+tags-title = タグ
+tag-not-found = タグが見つかりません
+tag-empty = このタグの短歌はまだありません
+
+## Print views
+
+print-title = { $title }（印刷）
+print-all-title = 本全体（印刷）
+print-back = < 戻る
+print-button = [印刷]
@@ -0,0 +1,2111 @@
+//! The reader UI: routes, pages and the small bits of browser state they keep
+//!
+//! Compiled into the WASM bundle (`csr` or `hydrate`) and, with the `ssr`
+//! feature, rendered to HTML by the `serve` binary.
+
+use leptos::prelude::*;
+use leptos_meta::{provide_meta_context, Link, Meta, Title};
+use leptos_router::components::*;
+use leptos_router::{path, NavigateOptions};
+use leptos_router::hooks::{use_location, use_navigate, use_params_map, use_query_map};
+use image::ImageEncoder;
+use std::time::Duration;
+
+use crate::art::{art_manifest, ArtFormat, MirroredArt};
+use crate::book::{book, fragment, neighbours, next_tanka, reading_order, BookEntry};
+use crate::embed::{best_player, pairing_links, read_player_message, Player, PlayerMessage};
+use crate::favicon::set_favicon;
+use crate::i18n::Locale;
+use crate::japanese::{parse_ruby, RubySegment};
+use crate::form::PoemForm;
+use crate::model::{all_tankas, MusicPairing, Poem, TankaEntry, AUTHOR};
+use crate::pick::{daily, random_slug, today};
+use crate::share::{quote, verse_id, verse_link, verse_number, VERSE_PARAM};
+use crate::shell::{self, Output as ShellOutput};
+use crate::storage::{ReaderData, Settings};
+use crate::tags::{tag, tag_counts, tagged, vocabulary, weight};
+use crate::theme::Theme;
+use crate::volume::{VolumeLevel, METER_STEPS};
+
+/// Rendered width of the album art, for `sizes` (matches `.album-art` in style.css)
+const ART_SIZES: &str = "(max-width: 600px) 240px, 180px";
+
+/// QR code sizes in pixels: on screen, and for print (where CSS pins it to a
+/// physical size that phones scan reliably)
+const QR_SCREEN_PX: u32 = 128;
+const QR_PRINT_PX: u32 = 512;
+
+/// Horizontal distance a swipe has to cover to turn the page
+const SWIPE_MIN_PX: i32 = 60;
+
+/// `ls -l` permissions of a tanka in the listing: read ones lose the write bit
+const UNREAD_PERMS: &str = "-rw-r--r--";
+const READ_PERMS: &str = "-r--r--r--";
+
+/// Reader preference for how tankas with content warnings are displayed
+#[derive(Debug, Clone, PartialEq)]
+enum WarningPreference {
+    /// Never blur, show everything immediately
+    AlwaysShow,
+    /// Blur every tanka that carries a warning until clicked through
+    AlwaysHide,
+    /// Blur only tankas carrying one of these warning categories
+    HideCategories(Vec<String>),
+}
+
+impl WarningPreference {
+    /// Whether a tanka with these warnings should start out blurred
+    fn hides(&self, warnings: &[String]) -> bool {
+        match self {
+            WarningPreference::AlwaysShow => false,
+            WarningPreference::AlwaysHide => !warnings.is_empty(),
+            WarningPreference::HideCategories(categories) => {
+                warnings.iter().any(|w| categories.contains(w))
+            }
+        }
+    }
+
+    /// The stored preference (hiding everything when there's none)
+    fn from_settings(settings: &Settings) -> WarningPreference {
+        match settings.cw_mode.as_deref() {
+            Some("show") => WarningPreference::AlwaysShow,
+            Some("custom") => WarningPreference::HideCategories(settings.cw_categories.clone()),
+            _ => WarningPreference::AlwaysHide,
+        }
+    }
+
+    fn save_to(&self, settings: &mut Settings) {
+        let (mode, categories) = match self {
+            WarningPreference::AlwaysShow => ("show", Vec::new()),
+            WarningPreference::AlwaysHide => ("hide", Vec::new()),
+            WarningPreference::HideCategories(c) => ("custom", c.clone()),
+        };
+        settings.cw_mode = Some(mode.to_string());
+        settings.cw_categories = categories;
+    }
+}
+
+/// Change what's kept about the reader: read fresh from localStorage (another
+/// tab may have changed it), apply `change`, save, and share it with the page
+fn update_reader(reader: RwSignal<ReaderData>, change: impl FnOnce(&mut ReaderData)) {
+    let mut data = ReaderData::load();
+    change(&mut data);
+    data.save();
+    reader.set(data);
+}
+
+/// Set `signal` to `value` only when it differs, so what depends on it doesn't
+/// rerun each time something else about the reader changes
+fn follow<T: PartialEq + Send + Sync + 'static>(signal: RwSignal<T>, value: T) {
+    signal.maybe_update(|current| {
+        let changed = *current != value;
+        if changed {
+            *current = value;
+        }
+        changed
+    });
+}
+
+/// Reader consent to the listening session: pairings start on their own and the
+/// book turns to the next tanka when the track ends
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ListeningSession {
+    /// Pause between a track ending and the next one
+    delay_secs: u32,
+}
+
+impl ListeningSession {
+    /// The stored session, only once the age gate is passed
+    fn from_settings(settings: &Settings) -> Option<ListeningSession> {
+        let delay_secs = settings.listening_session.filter(|_| settings.age_verified)?;
+        Some(ListeningSession { delay_secs })
+    }
+}
+
+/// Whether the reader has confirmed their age, shared so nothing starts
+/// playing behind the gate
+#[derive(Clone, Copy)]
+struct AgeVerified(RwSignal<bool>);
+
+/// Pauses offered in the session panel
+const SESSION_DELAYS: [u32; 4] = [0, 5, 15, 30];
+const DEFAULT_SESSION_DELAY: u32 = 5;
+
+/// Reader's theme choice: follow the browser's `prefers-color-scheme` /
+/// `prefers-contrast`, or one fixed theme
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ThemePreference {
+    System,
+    Fixed(Theme),
+}
+
+impl ThemePreference {
+    /// The theme this choice comes to on this browser
+    fn resolve(self) -> Theme {
+        match self {
+            ThemePreference::System => system_theme(),
+            ThemePreference::Fixed(theme) => theme,
+        }
+    }
+
+    /// The stored choice (following the system when there's none)
+    fn from_settings(settings: &Settings) -> ThemePreference {
+        match settings.theme.as_deref().and_then(Theme::from_name) {
+            Some(theme) => ThemePreference::Fixed(theme),
+            None => ThemePreference::System,
+        }
+    }
+
+    fn save_to(self, settings: &mut Settings) {
+        settings.theme = match self {
+            ThemePreference::System => None,
+            ThemePreference::Fixed(theme) => Some(theme.name().to_string()),
+        };
+    }
+}
+
+/// The theme the system asks for: style.css follows light and dark on its own,
+/// high contrast is set by [`apply_theme`]
+fn system_theme() -> Theme {
+    let matches = |query: &str| {
+        web_sys::window()
+            .and_then(|w| w.match_media(query).ok().flatten())
+            .is_some_and(|m| m.matches())
+    };
+
+    if matches("(prefers-contrast: more)") {
+        Theme::HighContrast
+    } else if matches("(prefers-color-scheme: light)") {
+        Theme::Light
+    } else {
+        Theme::Dark
+    }
+}
+
+/// Set `data-theme` on `<html>`, or clear it so style.css follows the system
+/// (which can't pick the high-contrast palette by itself)
+fn apply_theme(pref: ThemePreference) {
+    let root = match web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.document_element())
+    {
+        Some(root) => root,
+        None => return,
+    };
+
+    let _ = match (pref, pref.resolve()) {
+        (ThemePreference::System, Theme::HighContrast) => root.set_attribute("data-theme", Theme::HighContrast.name()),
+        (ThemePreference::System, _) => root.remove_attribute("data-theme"),
+        (ThemePreference::Fixed(theme), _) => root.set_attribute("data-theme", theme.name()),
+    };
+}
+
+/// Reader's language choice: negotiated from the browser's languages, or one fixed locale
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LocalePreference {
+    Browser,
+    Fixed(Locale),
+}
+
+impl LocalePreference {
+    /// The locale this choice comes to on this browser
+    fn resolve(self) -> Locale {
+        match self {
+            LocalePreference::Browser => browser_locale(),
+            LocalePreference::Fixed(locale) => locale,
+        }
+    }
+
+    /// The stored choice (following the browser when there's none)
+    fn from_settings(settings: &Settings) -> LocalePreference {
+        match settings.locale.as_deref().and_then(Locale::from_code) {
+            Some(locale) => LocalePreference::Fixed(locale),
+            None => LocalePreference::Browser,
+        }
+    }
+
+    fn save_to(self, settings: &mut Settings) {
+        settings.locale = match self {
+            LocalePreference::Browser => None,
+            LocalePreference::Fixed(locale) => Some(locale.code().to_string()),
+        };
+    }
+}
+
+/// Best locale for `navigator.languages`
+fn browser_locale() -> Locale {
+    let languages: Vec<String> = match web_sys::window() {
+        Some(window) => window
+            .navigator()
+            .languages()
+            .iter()
+            .filter_map(|lang| lang.as_string())
+            .collect(),
+        None => Vec::new(),
+    };
+    Locale::negotiate(&languages)
+}
+
+/// Set `lang` on `<html>` so fonts, hyphenation and screen readers follow the UI
+fn apply_locale(locale: Locale) {
+    if let Some(root) = web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.document_element())
+    {
+        let _ = root.set_attribute("lang", locale.code());
+    }
+}
+
+/// A UI string in the reader's language, updated when they switch.
+/// Only call inside a component, where the locale context is.
+fn tr(id: &'static str) -> impl Fn() -> String + Copy + Send + Sync + 'static {
+    let locale = expect_context::<RwSignal<Locale>>();
+    // The server reads `<Title>` text again after the page's reactive owner
+    // is gone; by then the default locale is what it rendered anyway
+    move || locale.try_get().unwrap_or_default().tr(id)
+}
+
+/// Age gate overlay component
+#[component]
+fn AgeGate(is_verified: RwSignal<bool>) -> impl IntoView {
+    let reader = expect_context::<RwSignal<ReaderData>>();
+    let on_enter = move |_| {
+        update_reader(reader, |r| r.settings.age_verified = true);
+        is_verified.set(true);
+    };
+
+    let on_exit = move |_| {
+        // Redirect away from the site
+        if let Some(window) = web_sys::window() {
+            let location = window.location();
+            let _ = location.assign("https://google.com");
+        }
+    };
+
+    view! {
+        <div class="age-gate-overlay">
+            <div class="age-gate-content">
+                <div class="age-gate-warning">{tr("age-gate-warning")}</div>
+                <div class="age-gate-title">{tr("age-gate-title")}</div>
+                <div class="age-gate-message">
+                    <p>{tr("age-gate-about")}</p>
+                    <p>{tr("age-gate-audience")}</p>
+                </div>
+                <div class="age-gate-buttons">
+                    <button class="age-gate-button exit" on:click=on_exit>{tr("age-gate-exit")}</button>
+                    <button class="age-gate-button enter" on:click=on_enter>{tr("age-gate-enter")}</button>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+/// Decode a blurhash into a tiny PNG data URI to show while the real art loads
+fn blurhash_data_uri(art: &MirroredArt) -> String {
+    use base64::Engine;
+
+    const WIDTH: u32 = 32;
+    let height = (WIDTH * art.height / art.width.max(1)).max(1);
+
+    let pixels = match blurhash::decode(&art.blurhash, WIDTH, height, 1.0) {
+        Ok(p) => p,
+        Err(_) => return String::new(),
+    };
+
+    let mut png_bytes: Vec<u8> = Vec::new();
+    let encoder = image::codecs::png::PngEncoder::new(&mut png_bytes);
+    if encoder.write_image(
+        &pixels,
+        WIDTH,
+        height,
+        image::ExtendedColorType::Rgba8,
+    ).is_err() {
+        return String::new();
+    }
+
+    let b64 = base64::engine::general_purpose::STANDARD.encode(&png_bytes);
+    format!("data:image/png;base64,{}", b64)
+}
+
+/// Album art, from the local mirror when `fetch-assets` has it, otherwise hotlinked
+#[component]
+fn AlbumArt(art_link: String) -> impl IntoView {
+    match art_manifest().get(&art_link).cloned() {
+        Some(art) => view! {
+            <div class="art-frame">
+                <img class="art-placeholder" src={blurhash_data_uri(&art)} alt="" aria-hidden="true" />
+                <picture>
+                    <source type={ArtFormat::Avif.mime()} srcset={art.srcset(ArtFormat::Avif)} sizes=ART_SIZES />
+                    <img
+                        src={art.fallback_url()}
+                        srcset={art.srcset(ArtFormat::Jpeg)}
+                        sizes=ART_SIZES
+                        width={art.width}
+                        height={art.height}
+                        alt=tr("art-alt")
+                    />
+                </picture>
+            </div>
+        }.into_any(),
+        None => view! {
+            <img src={art_link} alt=tr("art-alt") />
+        }.into_any(),
+    }
+}
+
+/// Generate QR code as base64 PNG data URI
+fn generate_qr_data_uri(url: &str, theme: Theme, min_px: u32) -> String {
+    use qrcode::QrCode;
+    use image::Rgb;
+    use base64::Engine;
+
+    let code = match QrCode::new(url.as_bytes()) {
+        Ok(c) => c,
+        Err(_) => return String::new(),
+    };
+
+    let (dark, light) = theme.qr_colors();
+    let image = code.render::<Rgb<u8>>()
+        .min_dimensions(min_px, min_px)
+        .dark_color(Rgb(dark))
+        .light_color(Rgb(light))
+        .build();
+
+    let mut png_bytes: Vec<u8> = Vec::new();
+    let encoder = image::codecs::png::PngEncoder::new(&mut png_bytes);
+    if encoder.write_image(
+        image.as_raw(),
+        image.width(),
+        image.height(),
+        image::ExtendedColorType::Rgb8,
+    ).is_err() {
+        return String::new();
+    }
+
+    let b64 = base64::engine::general_purpose::STANDARD.encode(&png_bytes);
+    format!("data:image/png;base64,{}", b64)
+}
+
+/// Index page with ls-style listing
+#[component]
+fn IndexPage() -> impl IntoView {
+    let tankas = all_tankas();
+    let warning_pref = RwSignal::new(WarningPreference::AlwaysHide);
+    Effect::new(move |_| warning_pref.set(WarningPreference::from_settings(&Settings::load())));
+
+    // Every warning category used anywhere in the book, for the settings panel
+    let mut categories: Vec<String> = tankas
+        .iter()
+        .flat_map(|t| t.tanka.content_warnings.iter().cloned())
+        .collect();
+    categories.sort();
+    categories.dedup();
+
+    // Chapters as book.yml lays them out, then anything it leaves out
+    let book = book();
+    let order = reading_order();
+    let total = order.len();
+    let listed: Vec<BookEntry> = book.entries().into_iter().cloned().collect();
+    let unsorted: Vec<BookEntry> = order.into_iter().filter(|e| !listed.contains(e)).collect();
+
+    // The same pick `/today` and the RSS feed lead with
+    let featured = daily(&tankas, today()).cloned();
+
+    // Where the reader left off, once their data is read
+    let reader = expect_context::<RwSignal<ReaderData>>();
+    let resume = {
+        let tankas = tankas.clone();
+        Memo::new(move |_| {
+            let slug = reader.with(|r| r.last_read.clone())?;
+            let entry = tankas.iter().find(|t| t.slug == slug)?;
+            Some((format!("/tanka/{}", entry.slug), entry.title()))
+        })
+    };
+    let total_tankas = tankas.len();
+
+    let first_slug = tankas.first().map(|t| t.slug.clone());
+    let shell_open = RwSignal::new(false);
+    let locale = expect_context::<RwSignal<Locale>>();
+
+    view! {
+        <Title text="" />
+        <Meta name="description" content=tr("site-description") />
+        <Meta property="og:title" content="Digital K-Hole" />
+        <Meta property="og:type" content="website" />
+        <Link rel="alternate" type_="application/rss+xml" title="Digital K-Hole" href="/rss.xml" />
+        <div class="page">
+            {featured.map(|entry| {
+                let href = format!("/tanka/{}", entry.slug);
+                let first_line = entry.tanka.tanka.as_vec().into_iter().find(|l| !l.trim().is_empty());
+                let first_line = first_line.unwrap_or_default().to_string();
+                view! {
+                    <div class="terminal featured">
+                        <div class="prompt">"$ today"</div>
+                        <a class="featured-tanka" href=href>
+                            <span class="featured-title">{entry.title()}</span>
+                            <span class="featured-verse">{first_line}</span>
+                        </a>
+                        <a class="featured-random" href="/random">{tr("random-link")}</a>
+                        {move || resume.get().map(|(href, title)| view! {
+                            <a class="featured-resume" href=href>{tr("resume")}" "{title}</a>
+                        })}
+                    </div>
+                }
+            })}
+            <div class="terminal">
+                <div class="prompt">"$ ls -la content/"</div>
+                <div class="ls-output">
+                    <div class="ls-header">"total "{total}</div>
+                    {book.chapters.into_iter().map(|chapter| view! {
+                        <div class="toc-chapter">
+                            <div class="toc-title">"# "{chapter.title}</div>
+                            {chapter.intro.map(|intro| view! { <div class="toc-intro">{intro}</div> })}
+                            {toc_rows(&chapter.entries, &tankas, warning_pref)}
+                            {chapter.sections.into_iter().map(|section| view! {
+                                <div class="toc-section">
+                                    <div class="toc-title">"## "{section.title}</div>
+                                    {section.intro.map(|intro| view! { <div class="toc-intro">{intro}</div> })}
+                                    {toc_rows(&section.entries, &tankas, warning_pref)}
+                                </div>
+                            }).collect_view()}
+                        </div>
+                    }).collect_view()}
+                    {(!unsorted.is_empty()).then(|| view! {
+                        <div class="toc-chapter">
+                            <div class="toc-title">"# "{tr("toc-unsorted")}</div>
+                            {toc_rows(&unsorted, &tankas, warning_pref)}
+                        </div>
+                    })}
+                </div>
+                <button class="shell-toggle" on:click=move |_| shell_open.update(|open| *open = !*open)>
+                    {move || locale.get().tr(if shell_open.get() { "shell-close" } else { "shell-open" })}
+                </button>
+            </div>
+            <Show when=move || shell_open.get()>
+                <Shell />
+            </Show>
+            <WarningSettings pref=warning_pref categories />
+            <SessionSettings first_slug />
+            <ThemeSettings />
+            <LocaleSettings />
+            <ReaderSettings total=total_tankas />
+            // Nothing to list until tags.yml has a theme
+            {(!vocabulary().is_empty()).then(|| view! {
+                <div class="terminal tags-link">
+                    <div class="prompt">"$ ls tags/"</div>
+                    <a href="/tags">{tr("tags-link")}</a>
+                </div>
+            })}
+            <div class="terminal soundtrack">
+                <div class="prompt">{tr("soundtrack-prompt")}</div>
+                <div class="soundtrack-formats">
+                    <a href="/soundtrack.m3u8" download="">"[m3u]"</a>
+                    <a href="/soundtrack.xspf" download="">"[xspf]"</a>
+                    <a href="/soundtrack.jspf" download="">"[jspf]"</a>
+                </div>
+            </div>
+            <div class="terminal print-all">
+                <div class="prompt">"$ lp --all"</div>
+                <a href="/print/all">{tr("print-all")}</a>
+            </div>
+        </div>
+    }
+}
+
+/// `ls -l`-style rows for a run of book entries; ones that don't exist are left out
+fn toc_rows(entries: &[BookEntry], tankas: &[TankaEntry], warning_pref: RwSignal<WarningPreference>) -> AnyView {
+    entries
+        .iter()
+        .filter_map(|entry| match entry {
+            BookEntry::Tanka(slug) => {
+                let entry = tankas.iter().find(|t| t.slug == *slug)?.clone();
+                Some(view! { <TankaRow entry warning_pref /> }.into_any())
+            }
+            BookEntry::Fragment(name) => {
+                fragment(name)?;
+                Some(view! {
+                    <a class="ls-row" href=entry.href()>
+                        <span class="ls-perms">"-r--r--r--"</span>
+                        <span class="ls-user">{AUTHOR}</span>
+                        <span class="ls-date">"2026-01-24"</span>
+                        <span class="ls-name">{format!("{}.fragmentedpoem", name)}</span>
+                    </a>
+                }.into_any())
+            }
+        })
+        .collect_view()
+        .into_any()
+}
+
+/// One tanka in the listing, with its content warnings. Read ones lose their
+/// write bit and dim; favorites get a star.
+#[component]
+fn TankaRow(entry: TankaEntry, warning_pref: RwSignal<WarningPreference>) -> impl IntoView {
+    let reader = expect_context::<RwSignal<ReaderData>>();
+    let slug = StoredValue::new(entry.slug.clone());
+    let is_read = move || reader.with(|r| slug.with_value(|s| r.is_read(s)));
+    let is_favorite = move || reader.with(|r| slug.with_value(|s| r.is_favorite(s)));
+    let warnings = entry.tanka.content_warnings;
+    let owner = entry.tanka.guest_contributor.map_or_else(|| AUTHOR.to_string(), |g| g.name);
+    view! {
+        <a class="ls-row" class=("read", is_read) href={format!("/tanka/{}", entry.slug)}>
+            <span class="ls-perms">{move || if is_read() { READ_PERMS } else { UNREAD_PERMS }}</span>
+            <span class="ls-user">{owner}</span>
+            <span class="ls-date">"2026-01-24"</span>
+            <span class="ls-name">{entry.filename}</span>
+            <Show when=is_favorite>
+                <span class="ls-fav">"★"</span>
+            </Show>
+            {(!warnings.is_empty()).then(|| view! {
+                <span class="ls-cw">
+                    "[cw: "
+                    {warnings.into_iter().map(|w| {
+                        // Highlighted when the reader's preference masks this category
+                        let masking = {
+                            let w = w.clone();
+                            move || warning_pref.get().hides(std::slice::from_ref(&w))
+                        };
+                        view! {
+                            <span class="ls-cw-tag" class=("masking", masking)>{w}</span>
+                        }
+                    }).collect_view()}
+                    "]"
+                </span>
+            })}
+        </a>
+    }
+}
+
+/// Prompt shown before every shell command
+const SHELL_PROMPT: &str = "guest@khole:~$ ";
+
+/// Interactive shell over the same tankas as the listing, for terminal people
+#[component]
+fn Shell() -> impl IntoView {
+    let tankas = StoredValue::new(all_tankas());
+    // (id, command line, output); the id keys <For> so players already on screen keep playing
+    let scrollback = RwSignal::new(Vec::<(usize, String, ShellOutput)>::new());
+    let next_id = StoredValue::new(0usize);
+    let input = RwSignal::new(String::new());
+    let history = StoredValue::new(Vec::<String>::new());
+    // Position while stepping through history; history.len() is the fresh line
+    let history_pos = StoredValue::new(0usize);
+    let input_ref = NodeRef::<leptos::html::Input>::new();
+
+    Effect::new(move |_| {
+        if let Some(el) = input_ref.get() {
+            let _ = el.focus();
+        }
+    });
+
+    let print = move |line: String, output: ShellOutput| {
+        let id = next_id.get_value();
+        next_id.set_value(id + 1);
+        scrollback.update(|s| s.push((id, line, output)));
+    };
+
+    let on_keydown = move |ev: leptos::ev::KeyboardEvent| match ev.key().as_str() {
+        "Enter" => {
+            let line = input.get_untracked();
+            let output = tankas.with_value(|t| shell::run(&line, t, js_sys::Math::random()));
+            if !line.trim().is_empty() {
+                history.update_value(|h| h.push(line.clone()));
+            }
+            history_pos.set_value(history.with_value(Vec::len));
+            input.set(String::new());
+            match output {
+                ShellOutput::Clear => scrollback.set(Vec::new()),
+                output => print(line, output),
+            }
+        }
+        "Tab" => {
+            ev.prevent_default();
+            let line = input.get_untracked();
+            let (completed, candidates) = tankas.with_value(|t| shell::complete(&line, t));
+            if !candidates.is_empty() {
+                print(line, ShellOutput::Text(vec![candidates.join("  ")]));
+            }
+            input.set(completed);
+        }
+        "ArrowUp" => {
+            ev.prevent_default();
+            let pos = history_pos.get_value();
+            if pos > 0 {
+                history_pos.set_value(pos - 1);
+                input.set(history.with_value(|h| h[pos - 1].clone()));
+            }
+        }
+        "ArrowDown" => {
+            ev.prevent_default();
+            let pos = history_pos.get_value() + 1;
+            let len = history.with_value(Vec::len);
+            if pos < len {
+                history_pos.set_value(pos);
+                input.set(history.with_value(|h| h[pos].clone()));
+            } else {
+                history_pos.set_value(len);
+                input.set(String::new());
+            }
+        }
+        _ => {}
+    };
+
+    let focus_input = move |_| {
+        if let Some(el) = input_ref.get_untracked() {
+            let _ = el.focus();
+        }
+    };
+
+    view! {
+        <div class="terminal shell" on:click=focus_input>
+            <div class="shell-text">{tr("shell-hint")}</div>
+            <For
+                each=move || scrollback.get()
+                key=|(id, _, _)| *id
+                children=move |(_, line, output)| view! {
+                    <div class="shell-entry">
+                        <div class="shell-line"><span class="shell-prompt">{SHELL_PROMPT}</span>{line}</div>
+                        <ShellResult output />
+                    </div>
+                }
+            />
+            <div class="shell-line">
+                <span class="shell-prompt">{SHELL_PROMPT}</span>
+                <input
+                    class="shell-input"
+                    type="text"
+                    autocomplete="off"
+                    autocapitalize="off"
+                    spellcheck="false"
+                    aria-label=tr("shell-input-label")
+                    node_ref=input_ref
+                    prop:value=move || input.get()
+                    on:input=move |ev| input.set(event_target_value(&ev))
+                    on:keydown=on_keydown
+                />
+            </div>
+        </div>
+    }
+}
+
+/// What one shell command printed
+#[component]
+fn ShellResult(output: ShellOutput) -> impl IntoView {
+    let find = |slug: &str| all_tankas().into_iter().find(|t| t.slug == slug);
+
+    match output {
+        ShellOutput::Nothing | ShellOutput::Clear => ().into_any(),
+        ShellOutput::Text(lines) => view! { <pre class="shell-text">{lines.join("\n")}</pre> }.into_any(),
+        ShellOutput::Error(message) => view! { <div class="shell-error">{message}</div> }.into_any(),
+        ShellOutput::Listing(lines) => lines
+            .into_iter()
+            .map(|(text, slug)| if slug.is_empty() {
+                view! { <pre class="shell-text">{text}</pre> }.into_any()
+            } else {
+                view! { <a class="shell-link" href=format!("/tanka/{}", slug)>{text}</a> }.into_any()
+            })
+            .collect_view()
+            .into_any(),
+        ShellOutput::Tanka(slug) => match find(&slug) {
+            Some(entry) => view! { <ShellTanka entry /> }.into_any(),
+            None => ().into_any(),
+        },
+        ShellOutput::Play(slug) => match find(&slug) {
+            Some(entry) => {
+                let pairing = entry.tanka.pairings().remove(0);
+                view! {
+                    <div class="shell-text">
+                        {format!("playing: {} by {}", pairing.track, pairing.artist)}
+                    </div>
+                    <MusicPlayer pairing on_ended=Callback::new(|_| {}) />
+                }.into_any()
+            }
+            None => ().into_any(),
+        },
+    }
+}
+
+/// `cat`: a tanka's verses and pairing, blurred like its page when it carries a warning
+#[component]
+fn ShellTanka(entry: TankaEntry) -> impl IntoView {
+    let locale = expect_context::<RwSignal<Locale>>();
+    let tanka = entry.tanka;
+    let warnings = tanka.content_warnings.clone();
+    let revealed = RwSignal::new(warnings.is_empty());
+    {
+        let warnings = warnings.clone();
+        Effect::new(move |_| revealed.set(!WarningPreference::from_settings(&Settings::load()).hides(&warnings)));
+    }
+    let pairing = tanka.pairings().remove(0);
+
+    view! {
+        <div class="shell-tanka">
+            <div class="shell-text">{tanka.top_flavor.clone()}</div>
+            {(!warnings.is_empty()).then(|| view! {
+                <div class="content-warning">
+                    <span class="cw-label">{tr("cw-label")}</span>
+                    <span class="cw-list">{warnings.join(", ")}</span>
+                    <button class="cw-reveal" on:click=move |_| revealed.update(|r| *r = !*r)>
+                        {move || locale.get().tr(if revealed.get() { "cw-hide" } else { "cw-show" })}
+                    </button>
+                </div>
+            })}
+            <div class="tanka-body" class=("cw-blurred", move || !revealed.get())>
+                <PoemBody form=tanka.form poem=tanka.tanka.clone() />
+            </div>
+            <div class="shell-text">
+                {format!("♪ {} by {} from {}", pairing.track, pairing.artist, pairing.album)}
+            </div>
+            <a class="shell-link" href=format!("/tanka/{}", entry.slug)>"open ->"</a>
+        </div>
+    }
+}
+
+/// Terminal-style panel for choosing how content warnings behave
+#[component]
+fn WarningSettings(pref: RwSignal<WarningPreference>, categories: Vec<String>) -> impl IntoView {
+    let reader = expect_context::<RwSignal<ReaderData>>();
+    let set_pref = move |new_pref: WarningPreference| {
+        update_reader(reader, |r| new_pref.save_to(&mut r.settings));
+        pref.set(new_pref);
+    };
+
+    let is_custom = move || matches!(pref.get(), WarningPreference::HideCategories(_));
+
+    view! {
+        <div class="terminal cw-settings">
+            <div class="prompt">"$ cw --mode"</div>
+            <div class="cw-modes">
+                <button
+                    class="cw-option"
+                    class=("active", move || pref.get() == WarningPreference::AlwaysShow)
+                    on:click=move |_| set_pref(WarningPreference::AlwaysShow)
+                >{tr("cw-always-show")}</button>
+                <button
+                    class="cw-option"
+                    class=("active", move || pref.get() == WarningPreference::AlwaysHide)
+                    on:click=move |_| set_pref(WarningPreference::AlwaysHide)
+                >{tr("cw-always-hide")}</button>
+                <button
+                    class="cw-option"
+                    class=("active", is_custom)
+                    on:click=move |_| {
+                        if !is_custom() {
+                            set_pref(WarningPreference::HideCategories(Vec::new()));
+                        }
+                    }
+                >{tr("cw-hide-some")}</button>
+            </div>
+            <Show when=is_custom>
+                <div class="cw-categories">
+                    {categories.clone().into_iter().map(|category| {
+                        let checked = {
+                            let category = category.clone();
+                            Memo::new(move |_| match pref.get() {
+                                WarningPreference::HideCategories(c) => c.contains(&category),
+                                _ => false,
+                            })
+                        };
+                        let toggle = {
+                            let category = category.clone();
+                            move |_| {
+                                let mut hidden = match pref.get_untracked() {
+                                    WarningPreference::HideCategories(c) => c,
+                                    _ => Vec::new(),
+                                };
+                                if let Some(pos) = hidden.iter().position(|c| c == &category) {
+                                    hidden.remove(pos);
+                                } else {
+                                    hidden.push(category.clone());
+                                }
+                                set_pref(WarningPreference::HideCategories(hidden));
+                            }
+                        };
+                        view! {
+                            <button class="cw-option" class=("active", checked) on:click=toggle>
+                                {move || if checked.get() { "[x] " } else { "[ ] " }}
+                                {category}
+                            </button>
+                        }
+                    }).collect_view()}
+                </div>
+            </Show>
+        </div>
+    }
+}
+
+/// Terminal-style panel for picking a theme
+#[component]
+fn ThemeSettings() -> impl IntoView {
+    let pref = expect_context::<RwSignal<ThemePreference>>();
+    let locale = expect_context::<RwSignal<Locale>>();
+    let reader = expect_context::<RwSignal<ReaderData>>();
+    let set_pref = move |new_pref: ThemePreference| {
+        update_reader(reader, |r| new_pref.save_to(&mut r.settings));
+        pref.set(new_pref);
+    };
+
+    view! {
+        <div class="terminal theme-settings">
+            <div class="prompt">"$ theme --set"</div>
+            <div class="theme-options">
+                <button
+                    class="theme-option"
+                    class=("active", move || pref.get() == ThemePreference::System)
+                    on:click=move |_| set_pref(ThemePreference::System)
+                >{tr("theme-system")}</button>
+                {Theme::ALL.into_iter().map(|theme| view! {
+                    <button
+                        class="theme-option"
+                        class=("active", move || pref.get() == ThemePreference::Fixed(theme))
+                        on:click=move |_| set_pref(ThemePreference::Fixed(theme))
+                    >{move || locale.get().tr(&format!("theme-{}", theme.name()))}</button>
+                }).collect_view()}
+            </div>
+        </div>
+    }
+}
+
+/// Terminal-style panel for picking the UI language
+#[component]
+fn LocaleSettings() -> impl IntoView {
+    let pref = expect_context::<RwSignal<LocalePreference>>();
+    let reader = expect_context::<RwSignal<ReaderData>>();
+    let set_pref = move |new_pref: LocalePreference| {
+        update_reader(reader, |r| new_pref.save_to(&mut r.settings));
+        pref.set(new_pref);
+    };
+
+    view! {
+        <div class="terminal locale-settings">
+            <div class="prompt">"$ locale --set"</div>
+            <div class="locale-options">
+                <button
+                    class="locale-option"
+                    class=("active", move || pref.get() == LocalePreference::Browser)
+                    on:click=move |_| set_pref(LocalePreference::Browser)
+                >{tr("locale-browser")}</button>
+                {Locale::ALL.into_iter().map(|locale| view! {
+                    <button
+                        class="locale-option"
+                        class=("active", move || pref.get() == LocalePreference::Fixed(locale))
+                        lang=locale.code()
+                        on:click=move |_| set_pref(LocalePreference::Fixed(locale))
+                    >{format!("[{}]", locale.label())}</button>
+                }).collect_view()}
+            </div>
+        </div>
+    }
+}
+
+/// What's kept about the reader, and moving it to another browser as JSON
+#[component]
+fn ReaderSettings(total: usize) -> impl IntoView {
+    let reader = expect_context::<RwSignal<ReaderData>>();
+    let locale = expect_context::<RwSignal<Locale>>();
+    let pasted = RwSignal::new(String::new());
+    let status = RwSignal::new(None::<String>);
+
+    let counts = move || {
+        let (favorites, read) = reader.with(|r| (r.favorites.len(), r.read.len()));
+        locale.get().tr_with(
+            "reader-counts",
+            &[("favorites", favorites.into()), ("read", read.into()), ("total", total.into())],
+        )
+    };
+    let export_href = move || json_data_uri(&reader.with(ReaderData::to_json));
+    let import = move |_| {
+        let message = match ReaderData::from_json(&pasted.get_untracked()) {
+            Ok(mut data) => {
+                let slugs: Vec<String> = all_tankas().into_iter().map(|t| t.slug).collect();
+                data.retain_known(&slugs);
+                data.save();
+                reader.set(data);
+                pasted.set(String::new());
+                locale.get_untracked().tr("reader-imported")
+            }
+            Err(e) => locale.get_untracked().tr_with("reader-import-failed", &[("error", e.into())]),
+        };
+        status.set(Some(message));
+    };
+
+    view! {
+        <div class="terminal reader-settings">
+            <div class="prompt">"$ cat ~/.khole/reader.json"</div>
+            <div class="reader-about">{tr("reader-about")}</div>
+            <div class="reader-counts">{counts}</div>
+            <div class="reader-options">
+                <a class="reader-option" href=export_href download="digital-khole-reader.json">{tr("reader-export")}</a>
+            </div>
+            <textarea
+                class="reader-import"
+                aria-label=tr("reader-import-label")
+                placeholder=tr("reader-import-label")
+                prop:value=move || pasted.get()
+                on:input=move |ev| pasted.set(event_target_value(&ev))
+            ></textarea>
+            <div class="reader-options">
+                <button class="reader-option" on:click=import>{tr("reader-import")}</button>
+                {move || status.get().map(|message| view! { <span class="reader-status">{message}</span> })}
+            </div>
+        </div>
+    }
+}
+
+/// A `data:` URL the export link downloads, percent-encoded in Rust so the
+/// server can render it too
+fn json_data_uri(json: &str) -> String {
+    let mut uri = String::from("data:application/json;charset=utf-8,");
+    for byte in json.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => uri.push(byte as char),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+/// Terminal-style panel for starting and stopping the listening session
+#[component]
+fn SessionSettings(first_slug: Option<String>) -> impl IntoView {
+    let session = expect_context::<RwSignal<Option<ListeningSession>>>();
+    let reader = expect_context::<RwSignal<ReaderData>>();
+    let AgeVerified(is_verified) = expect_context::<AgeVerified>();
+    let delay = RwSignal::new(DEFAULT_SESSION_DELAY);
+    Effect::new(move |_| {
+        if let Some(s) = session.get() {
+            delay.set(s.delay_secs);
+        }
+    });
+    let navigate = use_navigate();
+
+    let set_delay = move |secs: u32| {
+        delay.set(secs);
+        if session.get_untracked().is_some() {
+            start_session(session, reader, secs);
+        }
+    };
+
+    view! {
+        <div class="terminal session-settings">
+            <div class="prompt">"$ session --autoplay"</div>
+            <p class="session-about">{tr("session-about")}</p>
+            <div class="session-delays">
+                <span class="session-label">{tr("session-pause")}</span>
+                {SESSION_DELAYS.into_iter().map(|secs| view! {
+                    <button
+                        class="session-option"
+                        class=("active", move || delay.get() == secs)
+                        on:click=move |_| set_delay(secs)
+                    >{format!("[{}s]", secs)}</button>
+                }).collect_view()}
+            </div>
+            {move || if session.get().is_some() {
+                view! {
+                    <button class="session-option" on:click=move |_| stop_session(session, reader)>
+                        {tr("session-stop-long")}
+                    </button>
+                }.into_any()
+            } else {
+                let navigate = navigate.clone();
+                let first_slug = first_slug.clone();
+                view! {
+                    <button class="session-option" disabled=move || !is_verified.get() on:click=move |_| {
+                        start_session(session, reader, delay.get_untracked());
+                        if let Some(slug) = &first_slug {
+                            navigate(&format!("/tanka/{}", slug), Default::default());
+                        }
+                    }>{tr("session-start")}</button>
+                }.into_any()
+            }}
+        </div>
+    }
+}
+
+/// Session status on a tanka page, with the controls to start or stop it
+#[component]
+fn SessionBar(session: RwSignal<Option<ListeningSession>>, turning: RwSignal<bool>) -> impl IntoView {
+    let locale = expect_context::<RwSignal<Locale>>();
+    let reader = expect_context::<RwSignal<ReaderData>>();
+    let AgeVerified(is_verified) = expect_context::<AgeVerified>();
+
+    move || match session.get() {
+        Some(current) => view! {
+            <div class="session-bar active">
+                <span class="session-status">
+                    {move || if turning.get() && current.delay_secs > 0 {
+                        locale.get().tr_with("session-up-next", &[("secs", current.delay_secs.into())])
+                    } else {
+                        locale.get().tr("session-on")
+                    }}
+                </span>
+                <button class="session-option" on:click=move |_| stop_session(session, reader)>{tr("session-stop")}</button>
+            </div>
+        }.into_any(),
+        None => view! {
+            <div class="session-bar">
+                <button class="session-option" disabled=move || !is_verified.get() on:click=move |_| {
+                    start_session(session, reader, DEFAULT_SESSION_DELAY)
+                }>{tr("session-start")}</button>
+            </div>
+        }.into_any(),
+    }
+}
+
+fn start_session(session: RwSignal<Option<ListeningSession>>, reader: RwSignal<ReaderData>, delay_secs: u32) {
+    update_reader(reader, |r| r.settings.listening_session = Some(delay_secs));
+    session.set(Some(ListeningSession { delay_secs }));
+}
+
+fn stop_session(session: RwSignal<Option<ListeningSession>>, reader: RwSignal<ReaderData>) {
+    update_reader(reader, |r| r.settings.listening_session = None);
+    session.set(None);
+}
+
+/// The best embedded player for the pairing, then links to everywhere else it can be heard.
+///
+/// During a listening session the player starts on its own and `on_ended` runs
+/// when the track finishes (or straight away when there's nothing to play).
+#[component]
+fn MusicPlayer(pairing: MusicPairing, on_ended: Callback<()>) -> impl IntoView {
+    let session = expect_context::<RwSignal<Option<ListeningSession>>>();
+    let theme = expect_context::<RwSignal<Theme>>();
+    let locale = expect_context::<RwSignal<Locale>>();
+    let player = best_player(&pairing, &Theme::default().player_colors());
+    let text = pairing.text.clone();
+
+    // <audio> gets the pairing's volume applied; embedded players can't be
+    // controlled from outside their iframe, so they get a suggestion instead
+    let player_volume = pairing.volume_level.as_ref().and_then(|v| v.player_volume());
+    let volume_hint = player_volume
+        .filter(|_| matches!(player, Some(Player::Bandcamp(_) | Player::SoundCloud(_) | Player::YouTube(_))))
+        .map(|v| {
+            let percent = format!("{:.0}", v * 100.0);
+            move || locale.get().tr_with("volume-hint", &[("percent", percent.clone().into())])
+        });
+
+    // Whatever is embedded doesn't need repeating in the link list
+    let embedded = match &player {
+        Some(Player::Bandcamp(_)) => Some("bandcamp"),
+        Some(Player::SoundCloud(_)) => Some("soundcloud"),
+        Some(Player::YouTube(_)) => Some("youtube"),
+        Some(Player::Audio(_)) => Some("audio file"),
+        None => None,
+    };
+    let links: Vec<_> = pairing_links(&pairing)
+        .into_iter()
+        .filter(|link| Some(link.label) != embedded)
+        .collect();
+    let links_label = tr(if player.is_some() { "links-also" } else { "links-listen" });
+
+    // Embedded players report the end of the track over postMessage once asked to
+    let iframe = NodeRef::<leptos::html::Iframe>::new();
+    let subscription = player.as_ref().and_then(Player::end_subscription);
+    let subscribe = move || {
+        let (Some((origin, message)), Some(iframe)) = (subscription, iframe.get_untracked()) else {
+            return;
+        };
+        if let Some(frame) = iframe.content_window() {
+            let _ = frame.post_message(&wasm_bindgen::JsValue::from_str(message), origin);
+        }
+    };
+    if subscription.is_some() {
+        let handle = window_event_listener(leptos::ev::message, move |ev| {
+            let data = ev.data().as_string().unwrap_or_default();
+            match read_player_message(&ev.origin(), &data) {
+                Some(PlayerMessage::Ready) => subscribe(),
+                Some(PlayerMessage::Finished) => on_ended.run(()),
+                None => {}
+            }
+        });
+        on_cleanup(move || handle.remove());
+    }
+
+    // Nothing here can play (private album, no links), so the session moves on
+    let playable = player.is_some();
+    let manual = player.as_ref().is_some_and(|p| !p.reports_end());
+    Effect::new(move |_| {
+        if !playable && session.get().is_some() {
+            on_ended.run(());
+        }
+    });
+
+    let is_private = pairing.bandcamp_embed_isprivate;
+    // Re-picked so the players follow the theme's colors
+    let player_view = move || {
+        let autoplay = session.get().is_some();
+        let player = best_player(&pairing, &theme.get().player_colors())
+            .map(|p| if autoplay { p.autoplay() } else { p });
+        let allow = autoplay.then_some("autoplay");
+        match player {
+            Some(Player::Bandcamp(src)) => view! { <iframe class="bandcamp" src=src></iframe> }.into_any(),
+            Some(Player::SoundCloud(src)) => view! {
+                <iframe class="soundcloud" src=src allow=allow node_ref=iframe on:load=move |_| subscribe()></iframe>
+            }.into_any(),
+            Some(Player::YouTube(src)) => view! {
+                <iframe
+                    class="youtube"
+                    src=src
+                    allow=allow
+                    allowfullscreen=true
+                    node_ref=iframe
+                    on:load=move |_| subscribe()
+                ></iframe>
+            }.into_any(),
+            Some(Player::Audio(src)) => view! {
+                <audio
+                    controls=true
+                    preload="none"
+                    autoplay=autoplay
+                    src=src
+                    prop:volume=player_volume.unwrap_or(1.0)
+                    on:ended=move |_| on_ended.run(())
+                ></audio>
+            }.into_any(),
+            None if is_private => view! {
+                <div class="private-notice">{tr("private-notice")}</div>
+            }.into_any(),
+            None => ().into_any(),
+        }
+    };
+
+    // Bandcamp can't be started or followed from outside, so the reader steps in
+    let session_hint = move || session.get().map(|_| {
+        if !playable {
+            view! { <p class="session-hint">{tr("session-nothing")}</p> }.into_any()
+        } else if manual {
+            view! {
+                <p class="session-hint">
+                    {tr("session-bandcamp")}
+                    <button class="session-option" on:click=move |_| on_ended.run(())>{tr("session-next")}</button>
+                </p>
+            }.into_any()
+        } else {
+            ().into_any()
+        }
+    });
+
+    view! {
+        <div class="player">{player_view}</div>
+        {session_hint}
+        {volume_hint.map(|hint| view! { <p class="volume-hint">{hint}</p> })}
+        {(!links.is_empty() || text.is_some()).then(|| view! {
+            <div class="pairing-links">
+                {(!links.is_empty()).then(|| view! {
+                    <span class="pairing-links-label">{links_label}</span>
+                    {links.into_iter().map(|link| view! {
+                        <a href=link.url target="_blank" rel="noopener">{link.label}</a>
+                    }).collect_view()}
+                })}
+                {text.map(|text| view! { <p class="pairing-text">{text}</p> })}
+            </div>
+        })}
+    }
+}
+
+/// Five-step bar meter for a pairing's volume (nothing for free-text volumes)
+#[component]
+fn VolumeMeter(volume: VolumeLevel) -> impl IntoView {
+    let locale = expect_context::<RwSignal<Locale>>();
+
+    volume.meter().map(|level| view! {
+        <span
+            class="volume-meter"
+            title=move || locale.get().tr_with("volume-meter", &[("level", level.into()), ("steps", METER_STEPS.into())])
+        >
+            {(1..=METER_STEPS).map(|step| view! {
+                <span class="volume-step" class=("on", step <= level)></span>
+            }).collect_view()}
+        </span>
+    })
+}
+
+/// QR code, art, pairing line and player for one pairing
+#[component]
+fn PairingDetails(pairing: MusicPairing, on_ended: Callback<()>) -> impl IntoView {
+    let theme = expect_context::<RwSignal<Theme>>();
+    // Later setlist entries may have no page to point at, or no art
+    let qr_link = pairing.qr_link.clone();
+    let qr_code = qr_link.clone().map(|qr_link| view! {
+        <div class="qr-code">
+            <img src=move || generate_qr_data_uri(&qr_link, theme.get(), QR_SCREEN_PX) alt=tr("qr-alt") />
+        </div>
+    });
+    let art = pairing.art_link.clone().map(|art_link| view! {
+        <div class="album-art">
+            <AlbumArt art_link />
+        </div>
+    });
+    let note = pairing.note.clone();
+
+    view! {
+        <a class="media-row" href=qr_link target="_blank" rel="noopener">
+            {qr_code}
+            {art}
+
+            <div class="pairing">
+                <span class="track">{pairing.track.clone()}</span>
+                {tr("pairing-by")}
+                <span class="artist">{pairing.artist.clone()}</span>
+                {tr("pairing-from")}
+                <span class="album">{pairing.album.clone()}</span>
+                {pairing.volume_level.clone().map(|volume| view! {
+                    {tr("pairing-at")}
+                    <span class="volume">{volume.label().to_string()}</span>
+                    <VolumeMeter volume />
+                })}
+            </div>
+        </a>
+
+        {note.map(|note| view! { <p class="pairing-note">{note}</p> })}
+
+        <MusicPlayer pairing=pairing on_ended />
+    }
+}
+
+/// Numbered list of a tanka's pairings; picking one swaps the details above it
+#[component]
+fn Setlist(pairings: Vec<MusicPairing>, selected: RwSignal<usize>) -> impl IntoView {
+    view! {
+        <ol class="setlist">
+            {pairings.into_iter().enumerate().map(|(i, pairing)| view! {
+                <li class=("selected", move || selected.get() == i)>
+                    <button class="setlist-entry" on:click=move |_| selected.set(i)>
+                        <span class="setlist-number">{format!("{:02}.", i + 1)}</span>
+                        <span class="track">{pairing.track}</span>
+                        " - "
+                        <span class="artist">{pairing.artist}</span>
+                        {pairing.volume_level.map(|volume| view! {
+                            <span class="setlist-volume">{format!("[{}]", volume)}</span>
+                        })}
+                    </button>
+                    {pairing.note.map(|note| view! { <p class="setlist-note">{note}</p> })}
+                </li>
+            }).collect_view()}
+        </ol>
+    }
+}
+
+/// Single tanka page component
+#[component]
+fn TankaPageView() -> impl IntoView {
+    let params = use_params_map();
+
+    // The router keeps this view when only the slug changes, so rebuild the page per slug
+    move || {
+        let slug = params.read().get("slug").unwrap_or_default();
+        view! { <TankaPage slug /> }
+    }
+}
+
+/// One tanka, its pairings and the links either side of it
+#[component]
+fn TankaPage(slug: String) -> impl IntoView {
+    let entry = all_tankas().into_iter().find(|t| t.slug == slug);
+
+    match entry {
+        Some(entry) => {
+            // Prev/next follow the book across chapters; a session skips fragments
+            let order = reading_order();
+            let here = BookEntry::Tanka(slug.clone());
+            let (prev, next) = neighbours(&order, &here);
+            let (prev_href, next_href) = (prev.map(|e| e.href()), next.map(|e| e.href()));
+            let session_next = next_tanka(&order, &here);
+
+            let title = entry.title();
+            let related = entry.related();
+            let reader = expect_context::<RwSignal<ReaderData>>();
+            {
+                let slug = slug.clone();
+                Effect::new(move |_| update_reader(reader, |r| r.open(&slug)));
+            }
+            let locale = expect_context::<RwSignal<Locale>>();
+            let about_tanka = {
+                let entry = entry.clone();
+                move || entry.tankadesc(locale.get().code()).to_string()
+            };
+            let about_song = {
+                let entry = entry.clone();
+                move || entry.tastingnotes(locale.get().code()).map(|notes| view! {
+                    <p class="about-song">{notes.to_string()}</p>
+                })
+            };
+            let tanka = entry.tanka;
+            let ja_verses: Option<Vec<String>> = tanka.tanka.has_japanese().then(|| {
+                tanka.tanka.lines().iter().map(|v| v.ja.clone().unwrap_or_default()).collect()
+            });
+            let vertical = RwSignal::new(false);
+            let warnings = tanka.content_warnings.clone();
+            let tags = tanka.tags.clone();
+            let revealed = RwSignal::new(warnings.is_empty());
+            {
+                let warnings = warnings.clone();
+                Effect::new(move |_| revealed.set(!WarningPreference::from_settings(&Settings::load()).hides(&warnings)));
+            }
+
+            // The verse a verse link points at: `#v3` in the browser, `?v=3`
+            // where the fragment doesn't reach (the server, and so the card)
+            let location = use_location();
+            let query = use_query_map();
+            let highlighted = {
+                let poem = tanka.tanka.clone();
+                Memo::new(move |_| {
+                    verse_number(&location.hash.get())
+                        .or_else(|| query.read().get(VERSE_PARAM).as_deref().and_then(verse_number))
+                        .filter(|n| poem.verse(*n).is_some())
+                })
+            };
+            // Opened through one: bring it into view once it's rendered
+            Effect::new(move |_| {
+                if let Some(verse) = highlighted.get_untracked().and_then(|n| document().get_element_by_id(&verse_id(n))) {
+                    verse.scroll_into_view();
+                }
+            });
+
+            // A shared verse's card shows just that verse
+            let description = match highlighted.get_untracked().and_then(|n| tanka.tanka.verse(n)) {
+                Some(verse) => verse.text.trim().to_string(),
+                None => tanka
+                    .tanka
+                    .as_vec()
+                    .into_iter()
+                    .filter(|line| !line.trim().is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" / "),
+            };
+            let pairings = tanka.pairings();
+            let selected = RwSignal::new(0);
+            let og_image = pairings[0].art_link.clone().unwrap_or_default();
+
+            // Horizontal swipes turn the page: left for next, right for prev
+            let touch_start = StoredValue::new(None::<(i32, i32)>);
+            let on_touchstart = move |ev: leptos::ev::TouchEvent| {
+                if let Some(touch) = ev.changed_touches().get(0) {
+                    touch_start.set_value(Some((touch.client_x(), touch.client_y())));
+                }
+            };
+            let on_touchend = {
+                let navigate = use_navigate();
+                let prev_href = prev_href.clone();
+                let next_href = next_href.clone();
+                move |ev: leptos::ev::TouchEvent| {
+                    let (Some((x0, y0)), Some(touch)) = (touch_start.get_value(), ev.changed_touches().get(0)) else {
+                        return;
+                    };
+                    touch_start.set_value(None);
+
+                    let (dx, dy) = (touch.client_x() - x0, touch.client_y() - y0);
+                    if dx.abs() < SWIPE_MIN_PX || dx.abs() < 2 * dy.abs() {
+                        return;
+                    }
+                    let target = if dx < 0 { &next_href } else { &prev_href };
+                    if let Some(href) = target {
+                        navigate(href, Default::default());
+                    }
+                }
+            };
+
+            // Listening session: play through the setlist, then turn to the next tanka
+            let session = expect_context::<RwSignal<Option<ListeningSession>>>();
+            let turning = RwSignal::new(false);
+            let on_ended = {
+                let navigate = use_navigate();
+                let session_next = session_next.clone();
+                let pairing_count = pairings.len();
+                Callback::new(move |()| {
+                    let Some(current) = session.get_untracked() else { return };
+                    if turning.get_untracked() {
+                        return;
+                    }
+                    turning.set(true);
+
+                    let navigate = navigate.clone();
+                    let session_next = session_next.clone();
+                    set_timeout(
+                        move || {
+                            // The reader may have left the page or stopped the session meanwhile
+                            if turning.try_get_untracked() != Some(true) || session.get_untracked().is_none() {
+                                return;
+                            }
+                            turning.set(false);
+                            if selected.get_untracked() + 1 < pairing_count {
+                                selected.update(|i| *i += 1);
+                            } else if let Some(slug) = &session_next {
+                                navigate(&format!("/tanka/{}", slug), Default::default());
+                            } else {
+                                // End of the book
+                                stop_session(session, reader);
+                            }
+                        },
+                        Duration::from_secs(current.delay_secs.into()),
+                    );
+                })
+            };
+
+            view! {
+                <Title text=title.clone() />
+                <Meta name="description" content=description.clone() />
+                <Meta property="og:title" content=title.clone() />
+                <Meta property="og:description" content=description />
+                <Meta property="og:type" content="article" />
+                <Meta property="og:image" content=og_image />
+                <Meta name="twitter:card" content="summary" />
+                <div class="page" on:touchstart=on_touchstart on:touchend=on_touchend>
+                    <div class="tanka-header">
+                        {tanka.top_flavor}
+                    </div>
+
+                    {(pairings.len() > 1).then(|| view! {
+                        <Setlist pairings=pairings.clone() selected=selected />
+                    })}
+
+                    <SessionBar session turning />
+
+                    {move || view! {
+                        <PairingDetails pairing=pairings[selected.get()].clone() on_ended />
+                    }}
+
+                    {(!warnings.is_empty()).then(|| view! {
+                        <div class="content-warning">
+                            <span class="cw-label">{tr("cw-label")}</span>
+                            <span class="cw-list">{warnings.join(", ")}</span>
+                            <button class="cw-reveal" on:click=move |_| revealed.update(|r| *r = !*r)>
+                                {move || locale.get().tr(if revealed.get() { "cw-hide" } else { "cw-show" })}
+                            </button>
+                        </div>
+                    })}
+
+                    {ja_verses.is_some().then(|| view! {
+                        <div class="ja-toggle">
+                            <button class="ja-option" on:click=move |_| vertical.update(|v| *v = !*v)>
+                                {move || locale.get().tr(if vertical.get() { "ja-horizontal" } else { "ja-vertical" })}
+                            </button>
+                        </div>
+                    })}
+
+                    <div
+                        class="tanka-body"
+                        class=("cw-blurred", move || !revealed.get())
+                        class=("bilingual", move || vertical.get())
+                    >
+                        {ja_verses.map(|ja| view! {
+                            <Show when=move || vertical.get()>
+                                <JapaneseVerses verses=ja.clone() />
+                            </Show>
+                        })}
+                        <PoemBody form=tanka.form poem=tanka.tanka.clone() highlighted />
+                    </div>
+
+                    <VerseShare slug=slug.clone() title poem=tanka.tanka.clone() highlighted />
+
+                    <div class="commentary" class=("cw-blurred", move || !revealed.get())>
+                        <p class="about-tanka">{about_tanka}</p>
+                        {about_song}
+                    </div>
+
+                    {(!related.is_empty()).then(|| view! {
+                        <div class="see-also">
+                            <span class="see-also-label">{tr("see-also")}</span>
+                            {related.into_iter().map(|other| {
+                                let href = format!("/tanka/{}", other.slug);
+                                view! { <a class="see-also-link" href=href>{other.title()}</a> }
+                            }).collect_view()}
+                        </div>
+                    })}
+
+                    <ReaderActions slug=slug.clone() />
+
+                    {(!tags.is_empty()).then(|| view! {
+                        <div class="tag-chips">
+                            {tags.into_iter().map(|name| {
+                                let href = format!("/tag/{}", name);
+                                view! { <a class="tag-chip" href=href>"#"{name}</a> }
+                            }).collect_view()}
+                        </div>
+                    })}
+
+                    <nav class="tanka-nav">
+                        <div class="nav-prev">
+                            {prev_href.map(|href| view! { <a href=href>{tr("nav-prev")}</a> })}
+                        </div>
+                        <div class="nav-index">
+                            <a href="/">"[ls]"</a>
+                            " "
+                            <a href={format!("/tanka/{}/print", slug)}>{tr("nav-print")}</a>
+                        </div>
+                        <div class="nav-next">
+                            {next_href.map(|href| view! { <a href=href>{tr("nav-next")}</a> })}
+                        </div>
+                    </nav>
+                </div>
+            }.into_any()
+        }
+        None => view! {
+            <Title text=tr("not-found") />
+            <div class="page">
+                <div class="error">{tr("not-found")}</div>
+                <a href="/">{tr("back-to-index")}</a>
+            </div>
+        }.into_any()
+    }
+}
+
+/// `/today`: on to the day's tanka (`serve` redirects before this renders)
+#[component]
+fn TodayPage() -> impl IntoView {
+    let path = daily(&all_tankas(), today()).map_or_else(|| "/".to_string(), |entry| format!("/tanka/{}", entry.slug));
+    view! { <Redirect path /> }
+}
+
+/// `/random`: on to a tanka the reader hasn't just seen. Picked in the
+/// browser, where the recently seen list is.
+#[component]
+fn RandomPage() -> impl IntoView {
+    let navigate = use_navigate();
+    Effect::new(move |_| {
+        let slugs: Vec<String> = all_tankas().into_iter().map(|t| t.slug).collect();
+        let path = random_slug(&slugs, &ReaderData::load().recently_seen, js_sys::Math::random())
+            .map_or_else(|| "/".to_string(), |slug| format!("/tanka/{}", slug));
+        navigate(&path, NavigateOptions { replace: true, ..Default::default() });
+    });
+
+    view! {
+        <Title text=tr("random-picking") />
+        <div class="page">
+            <div class="terminal">
+                <div class="prompt">"$ shuf -n 1 content/*.yml"</div>
+                <div class="random-picking">{tr("random-picking")}</div>
+            </div>
+        </div>
+    }
+}
+
+/// Every tag with how many tankas carry it, sized by use
+#[component]
+fn TagsPage() -> impl IntoView {
+    let counts = tag_counts(&all_tankas());
+    let max = counts.iter().map(|(_, count)| *count).max().unwrap_or(0);
+
+    view! {
+        <Title text=tr("tags-title") />
+        <div class="page">
+            <div class="terminal">
+                <div class="prompt">"$ ls tags/"</div>
+                <div class="tag-cloud">
+                    {counts.into_iter().map(|(tag, count)| {
+                        let href = tag.href();
+                        view! {
+                            <a class=format!("tag-chip weight-{}", weight(count, max)) href=href title=tag.about>
+                                "#"{tag.name}
+                                <span class="tag-count">{count}</span>
+                            </a>
+                        }
+                    }).collect_view()}
+                </div>
+            </div>
+            <nav class="tanka-nav">
+                <div class="nav-index">
+                    <a href="/">"[ls]"</a>
+                </div>
+            </nav>
+        </div>
+    }
+}
+
+/// Tankas tagged with one theme, at `/tag/:name`
+#[component]
+fn TagPageView() -> impl IntoView {
+    let params = use_params_map();
+
+    move || {
+        let name = params.read().get("name").unwrap_or_default();
+        view! { <TagPage name /> }
+    }
+}
+
+/// One tag's listing, in book order, with the same rows as the index
+#[component]
+fn TagPage(name: String) -> impl IntoView {
+    let Some(tag) = tag(&name) else {
+        return view! {
+            <Title text=tr("tag-not-found") />
+            <div class="page">
+                <div class="error">{tr("tag-not-found")}</div>
+                <a href="/tags">{tr("tags-link")}</a>
+            </div>
+        }
+        .into_any();
+    };
+
+    let warning_pref = RwSignal::new(WarningPreference::AlwaysHide);
+    Effect::new(move |_| warning_pref.set(WarningPreference::from_settings(&Settings::load())));
+    let tankas: Vec<TankaEntry> = tagged(&all_tankas(), &tag.name).into_iter().cloned().collect();
+    let title = format!("#{}", tag.name);
+
+    view! {
+        <Title text=title.clone() />
+        <Meta name="description" content=tag.about.clone() />
+        <Meta property="og:title" content=title />
+        <div class="page">
+            <div class="terminal">
+                <div class="prompt">{format!("$ ls tags/{}/", tag.name)}</div>
+                <div class="tag-about">{tag.about}</div>
+                <div class="ls-output">
+                    <div class="ls-header">"total "{tankas.len()}</div>
+                    {tankas.is_empty().then(|| view! { <div class="tag-empty">{tr("tag-empty")}</div> })}
+                    {tankas.into_iter().map(|entry| view! { <TankaRow entry warning_pref /> }).collect_view()}
+                </div>
+            </div>
+            <nav class="tanka-nav">
+                <div class="nav-index">
+                    <a href="/">"[ls]"</a>
+                    " "
+                    <a href="/tags">{tr("tags-link")}</a>
+                </div>
+            </nav>
+        </div>
+    }
+    .into_any()
+}
+
+/// A fragment the book reads, at `/fragment/:name`
+#[component]
+fn FragmentPageView() -> impl IntoView {
+    let params = use_params_map();
+
+    move || {
+        let name = params.read().get("name").unwrap_or_default();
+        view! { <FragmentPage name /> }
+    }
+}
+
+/// An unfinished poem, shown as written: its lines, in stanzas split on blank
+/// lines, between the entries either side of it
+#[component]
+fn FragmentPage(name: String) -> impl IntoView {
+    let Some(text) = fragment(&name) else {
+        return view! {
+            <Title text=tr("fragment-not-found") />
+            <div class="page">
+                <div class="error">{tr("fragment-not-found")}</div>
+                <a href="/">{tr("back-to-index")}</a>
+            </div>
+        }
+        .into_any();
+    };
+
+    let (prev, next) = neighbours(&reading_order(), &BookEntry::Fragment(name.clone()));
+    let stanzas: Vec<Vec<String>> = text
+        .split("\n\n")
+        .map(|stanza| stanza.lines().map(str::to_string).filter(|l| !l.trim().is_empty()).collect::<Vec<_>>())
+        .filter(|lines| !lines.is_empty())
+        .collect();
+    let title = format!("{}.fragmentedpoem", name);
+
+    view! {
+        <Title text=title.clone() />
+        <Meta property="og:title" content=title.clone() />
+        <Meta property="og:type" content="article" />
+        <div class="page">
+            <div class="tanka-header">{title}</div>
+            <div class="tanka-body">
+                <div class="tanka-en form-free-verse fragment">
+                    {stanzas.into_iter().map(|lines| view! {
+                        <div class="stanza">
+                            {lines.into_iter().map(|line| view! { <div class="tanka-verse">{line}</div> }).collect_view()}
+                        </div>
+                    }).collect_view()}
+                </div>
+            </div>
+            <nav class="tanka-nav">
+                <div class="nav-prev">
+                    {prev.map(|entry| view! { <a href=entry.href()>{tr("nav-prev")}</a> })}
+                </div>
+                <div class="nav-index">
+                    <a href="/">"[ls]"</a>
+                </div>
+                <div class="nav-next">
+                    {next.map(|entry| view! { <a href=entry.href()>{tr("nav-next")}</a> })}
+                </div>
+            </nav>
+        </div>
+    }
+    .into_any()
+}
+
+/// Favorite a tanka, or mark it unread again (opening it marks it read)
+#[component]
+fn ReaderActions(slug: String) -> impl IntoView {
+    let reader = expect_context::<RwSignal<ReaderData>>();
+    let locale = expect_context::<RwSignal<Locale>>();
+    let slug = StoredValue::new(slug);
+    let is_favorite = move || reader.with(|r| slug.with_value(|s| r.is_favorite(s)));
+    let is_read = move || reader.with(|r| slug.with_value(|s| r.is_read(s)));
+
+    view! {
+        <div class="reader-actions">
+            <button
+                class="reader-option"
+                class=("active", is_favorite)
+                on:click=move |_| update_reader(reader, |r| slug.with_value(|s| r.toggle_favorite(s)))
+            >
+                {move || locale.get().tr(if is_favorite() { "fav-remove" } else { "fav-add" })}
+            </button>
+            <button
+                class="reader-option"
+                on:click=move |_| {
+                    let read = !is_read();
+                    update_reader(reader, |r| slug.with_value(|s| r.set_read(s, read)));
+                }
+            >
+                {move || locale.get().tr(if is_read() { "read-unmark" } else { "read-mark" })}
+            </button>
+        </div>
+    }
+}
+
+/// A poem's lines, laid out for its form: one block for tanka, haiku and
+/// cinquain, sedoka's two halves, free verse in its stanzas. On a tanka's own
+/// page each verse gets an id and a link to itself, and `highlighted` marks
+/// the one a verse link opened.
+#[component]
+fn PoemBody(form: PoemForm, poem: Poem, #[prop(optional)] highlighted: Option<Memo<Option<usize>>>) -> impl IntoView {
+    // Numbered as `Poem::verse` counts: the lines shown, from 1
+    let mut shown = 0;
+    let stanzas: Vec<Vec<(Option<usize>, String)>> = poem
+        .stanzas(form)
+        .into_iter()
+        .map(|lines| {
+            lines
+                .iter()
+                .map(|v| {
+                    let n = (!v.text.trim().is_empty()).then(|| {
+                        shown += 1;
+                        shown
+                    });
+                    (n, v.text.clone())
+                })
+                .collect()
+        })
+        .collect();
+
+    let verse = move |(n, line): (Option<usize>, String)| match (highlighted, n) {
+        (Some(highlighted), Some(n)) => view! {
+            <div class="tanka-verse" id=verse_id(n) class=("highlighted", move || highlighted.get() == Some(n))>
+                {line}
+                <a class="verse-link" href=verse_link(n) aria-label=tr("verse-link") title=tr("verse-link")>"#"</a>
+            </div>
+        }.into_any(),
+        _ => view! { <div class="tanka-verse">{line}</div> }.into_any(),
+    };
+
+    view! {
+        <div class=format!("tanka-en form-{}", form.name())>
+            {stanzas.into_iter().map(|lines| view! {
+                <div class="stanza">{lines.into_iter().map(verse).collect_view()}</div>
+            }).collect_view()}
+        </div>
+    }
+}
+
+/// Under a poem opened through a verse link: the verse, and a button copying
+/// it with its link
+#[component]
+fn VerseShare(slug: String, title: String, poem: Poem, highlighted: Memo<Option<usize>>) -> impl IntoView {
+    let locale = expect_context::<RwSignal<Locale>>();
+    let status = RwSignal::new(None::<&'static str>);
+    let quoted = StoredValue::new((slug, title, poem));
+    let verse = move || {
+        let n = highlighted.get()?;
+        quoted.with_value(|(_, _, poem)| poem.verse(n).map(|v| (n, v.text.clone())))
+    };
+
+    // Another verse, a fresh button
+    Effect::new(move |_| {
+        highlighted.track();
+        status.set(None);
+    });
+
+    let copy = move |_| {
+        let (Some((n, line)), Some(window)) = (verse(), web_sys::window()) else {
+            return;
+        };
+        let text = quoted.with_value(|(slug, title, _)| {
+            let origin = window.location().origin().unwrap_or_default();
+            quote(&line, title, &format!("{}/tanka/{}{}", origin, slug, verse_link(n)))
+        });
+
+        // No clipboard outside secure contexts
+        let clipboard = window.navigator().clipboard();
+        if clipboard.is_undefined() {
+            status.set(Some("share-failed"));
+            return;
+        }
+        let written = wasm_bindgen_futures::JsFuture::from(clipboard.write_text(&text));
+        leptos::task::spawn_local(async move {
+            status.set(Some(if written.await.is_ok() { "share-copied" } else { "share-failed" }));
+        });
+    };
+
+    move || verse().map(|(_, line)| view! {
+        <div class="verse-share">
+            <span class="verse-share-quote">"“"{line}"”"</span>
+            <button class="reader-option" on:click=copy>{move || locale.get().tr("share-copy")}</button>
+            {move || status.get().map(|id| view! { <span class="reader-status">{locale.get().tr(id)}</span> })}
+        </div>
+    })
+}
+
+/// Japanese verses in vertical writing, with readings as ruby
+#[component]
+fn JapaneseVerses(verses: Vec<String>) -> impl IntoView {
+    view! {
+        <div class="tanka-ja" lang="ja">
+            {verses.into_iter().map(|verse| {
+                // The validator reports bad markup; show it raw rather than nothing
+                let line = match parse_ruby(&verse) {
+                    Ok(segments) => segments.into_iter().map(|segment| match segment {
+                        RubySegment::Plain(text) => text.into_any(),
+                        RubySegment::Ruby { base, reading } => view! {
+                            <ruby>{base}<rt>{reading}</rt></ruby>
+                        }.into_any(),
+                    }).collect_view().into_any(),
+                    Err(_) => verse.into_any(),
+                };
+                view! { <p class="ja-verse">{line}</p> }
+            }).collect_view()}
+        </div>
+    }
+}
+
+/// Printable single tanka
+#[component]
+fn TankaPrintView() -> impl IntoView {
+    let params = use_params_map();
+    let locale = expect_context::<RwSignal<Locale>>();
+
+    move || {
+        let slug = params.read().get("slug").unwrap_or_default();
+        match all_tankas().into_iter().find(|t| t.slug == slug) {
+            Some(entry) => {
+                let title = entry.title();
+                view! {
+                    <Title text=move || locale.get().tr_with("print-title", &[("title", title.clone().into())]) />
+                    <PrintPage back=format!("/tanka/{}", slug)>
+                        <PrintedTanka entry />
+                    </PrintPage>
+                }.into_any()
+            }
+            None => view! {
+                <Title text=tr("not-found") />
+                <div class="page">
+                    <div class="error">{tr("not-found")}</div>
+                    <a href="/">{tr("back-to-index")}</a>
+                </div>
+            }.into_any(),
+        }
+    }
+}
+
+/// The whole book, one tanka per printed page
+#[component]
+fn PrintAllView() -> impl IntoView {
+    view! {
+        <Title text=tr("print-all-title") />
+        <PrintPage back="/".to_string()>
+            {all_tankas().into_iter().map(|entry| view! { <PrintedTanka entry /> }).collect_view()}
+        </PrintPage>
+    }
+}
+
+/// Print toolbar (screen only) and the age warning that opens every printout
+#[component]
+fn PrintPage(back: String, children: Children) -> impl IntoView {
+    let on_print = move |_| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.print();
+        }
+    };
+
+    view! {
+        <div class="page print-page">
+            <div class="print-toolbar">
+                <a href=back>{tr("print-back")}</a>
+                <button class="print-button" on:click=on_print>{tr("print-button")}</button>
+            </div>
+            <div class="print-warning">
+                <div class="print-warning-title">{tr("print-warning-title")}</div>
+                <p>{tr("print-warning-about")}</p>
+                <p>{tr("age-gate-audience")}</p>
+            </div>
+            {children()}
+        </div>
+    }
+}
+
+/// One tanka laid out for paper: no player or navigation, a full-size QR code,
+/// print-resolution art and every pairing in the setlist
+#[component]
+fn PrintedTanka(entry: TankaEntry) -> impl IntoView {
+    let locale = expect_context::<RwSignal<Locale>>();
+    let about_tanka = {
+        let entry = entry.clone();
+        move || entry.tankadesc(locale.get().code()).to_string()
+    };
+    let about_song = {
+        let entry = entry.clone();
+        move || entry.tastingnotes(locale.get().code()).map(|notes| view! {
+            <p class="about-song">{notes.to_string()}</p>
+        })
+    };
+    let tanka = entry.tanka;
+    let pairings = tanka.pairings();
+    let warnings = tanka.content_warnings.clone();
+
+    view! {
+        <article class="print-tanka">
+            <div class="tanka-header">{tanka.top_flavor.clone()}</div>
+
+            {pairings.into_iter().map(|pairing| {
+                let qr_link = pairing.qr_link.clone();
+                let qr_code = qr_link.as_deref().map(|qr_link| {
+                    let qr_src = generate_qr_data_uri(qr_link, Theme::default(), QR_PRINT_PX);
+                    view! {
+                        <div class="qr-code">
+                            <img src={qr_src} alt=tr("qr-alt") />
+                        </div>
+                    }
+                });
+                let art = pairing.art_link.clone().map(|art_link| view! {
+                    <div class="album-art">
+                        <PrintArt art_link />
+                    </div>
+                });
+                view! {
+                    <div class="media-row">
+                        {qr_code}
+                        {art}
+                        <div class="pairing">
+                            <span class="track">{pairing.track}</span>
+                            {tr("pairing-by")}
+                            <span class="artist">{pairing.artist}</span>
+                            {tr("pairing-from")}
+                            <span class="album">{pairing.album}</span>
+                            {pairing.volume_level.map(|volume| view! {
+                                {tr("pairing-at")}
+                                <span class="volume">{volume.label().to_string()}</span>
+                            })}
+                            <span class="print-link">{qr_link}</span>
+                        </div>
+                    </div>
+                }
+            }).collect_view()}
+
+            {(!warnings.is_empty()).then(|| view! {
+                <div class="content-warning">
+                    <span class="cw-label">{tr("cw-label")}</span>
+                    <span class="cw-list">{warnings.join(", ")}</span>
+                </div>
+            })}
+
+            <div class="tanka-body">
+                <PoemBody form=tanka.form poem=tanka.tanka.clone() />
+            </div>
+
+            <div class="commentary">
+                <p class="about-tanka">{about_tanka}</p>
+                {about_song}
+            </div>
+        </article>
+    }
+}
+
+/// Album art at print resolution: the largest mirrored JPEG, or the original link
+#[component]
+fn PrintArt(art_link: String) -> impl IntoView {
+    match art_manifest().get(&art_link) {
+        Some(art) => view! {
+            <img src={art.print_url()} width={art.width} height={art.height} alt=tr("art-alt") />
+        }.into_any(),
+        None => view! { <img src={art_link} alt=tr("art-alt") /> }.into_any(),
+    }
+}
+
+/// Root component: router plus the age gate laid over it.
+///
+/// The routes always render so server-rendered HTML carries the full page;
+/// the gate covers them until the reader has confirmed their age.
+#[component]
+pub fn App() -> impl IntoView {
+    provide_meta_context();
+
+    // Favorites, reading progress and the reader's settings, kept in the browser.
+    // localStorage only exists there, so start from defaults and read it once mounted
+    let reader = RwSignal::new(ReaderData::default());
+    Effect::new(move |_| reader.set(ReaderData::load()));
+    provide_context(reader);
+
+    // The settings below follow what's stored, so an import applies them too.
+    // Start gated until the stored answer is read
+    let is_verified = RwSignal::new(false);
+    Effect::new(move |_| follow(is_verified, reader.with(|r| r.settings.age_verified)));
+    provide_context(AgeVerified(is_verified));
+
+    // The listening session, shared by every page
+    let session = RwSignal::new(None::<ListeningSession>);
+    Effect::new(move |_| follow(session, reader.with(|r| ListeningSession::from_settings(&r.settings))));
+    provide_context(session);
+
+    // Theme: style.css follows the system until the stored choice is read, then
+    // the resolved theme colors the QR codes, favicon and players
+    let theme_pref = RwSignal::new(ThemePreference::System);
+    let theme = RwSignal::new(Theme::default());
+    Effect::new(move |_| follow(theme_pref, reader.with(|r| ThemePreference::from_settings(&r.settings))));
+    Effect::new(move |_| {
+        let pref = theme_pref.get();
+        apply_theme(pref);
+        theme.set(pref.resolve());
+    });
+    Effect::new(move |_| set_favicon(theme.get().favicon_tint()));
+    provide_context(theme_pref);
+    provide_context(theme);
+
+    // Language: the server renders English, then the browser's languages or the
+    // stored choice take over
+    let locale_pref = RwSignal::new(LocalePreference::Browser);
+    let locale = RwSignal::new(Locale::default());
+    Effect::new(move |_| follow(locale_pref, reader.with(|r| LocalePreference::from_settings(&r.settings))));
+    Effect::new(move |_| {
+        let current = locale_pref.get().resolve();
+        apply_locale(current);
+        locale.set(current);
+    });
+    provide_context(locale_pref);
+    provide_context(locale);
+
+    view! {
+        <Title formatter=|text: String| {
+            if text.is_empty() {
+                "Digital K-Hole".to_string()
+            } else {
+                format!("{} // Digital K-Hole", text)
+            }
+        } />
+        <Router>
+            <Routes fallback=|| view! { <div>{tr("page-not-found")}</div> }>
+                <Route path=path!("/") view=IndexPage />
+                <Route path=path!("/tanka/:slug") view=TankaPageView />
+                <Route path=path!("/tanka/:slug/print") view=TankaPrintView />
+                <Route path=path!("/fragment/:name") view=FragmentPageView />
+                <Route path=path!("/today") view=TodayPage />
+                <Route path=path!("/random") view=RandomPage />
+                <Route path=path!("/tags") view=TagsPage />
+                <Route path=path!("/tag/:name") view=TagPageView />
+                <Route path=path!("/print/all") view=PrintAllView />
+            </Routes>
+        </Router>
+        <Show when=move || !is_verified.get()>
+            <AgeGate is_verified />
+        </Show>
+    }
+}
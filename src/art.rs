@@ -0,0 +1,120 @@
+//! Locally mirrored album art
+//!
+//! `fetch-assets` downloads every `art_link` into a content-addressed store
+//! (`assets/art/`) and records what it produced in `assets/art/manifest.yml`.
+//! Trunk copies the store to `/art/`, and pages use these copies instead of
+//! hotlinking bcbits.com. Anything not in the manifest falls back to `art_link`.
+
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+/// Widths (px) every image is resized to, capped at the original width
+pub const VARIANT_WIDTHS: &[u32] = &[180, 360, 720];
+
+/// URL prefix the store is served under (see `index.html`)
+pub const ART_URL_PREFIX: &str = "/art";
+
+/// Encodings generated for each width, best first
+///
+/// No WebP: the `image` crate only encodes it losslessly, which comes out
+/// several times larger than the JPEG it would be offered instead of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtFormat {
+    Avif,
+    Jpeg,
+}
+
+impl ArtFormat {
+    pub const ALL: [ArtFormat; 2] = [ArtFormat::Avif, ArtFormat::Jpeg];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ArtFormat::Avif => "avif",
+            ArtFormat::Jpeg => "jpg",
+        }
+    }
+
+    pub fn mime(self) -> &'static str {
+        match self {
+            ArtFormat::Avif => "image/avif",
+            ArtFormat::Jpeg => "image/jpeg",
+        }
+    }
+}
+
+/// One downloaded image and the variants generated from it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MirroredArt {
+    /// sha256 of the original bytes, hex encoded
+    pub hash: String,
+    /// Extension of the original download (`jpg`, `png`, ...)
+    pub original_ext: String,
+    pub width: u32,
+    pub height: u32,
+    /// Low-res placeholder shown while the real image loads
+    pub blurhash: String,
+    /// Widths that were actually generated (never upscaled)
+    pub widths: Vec<u32>,
+}
+
+impl MirroredArt {
+    /// File name of the untouched download inside the store
+    pub fn original_file(&self) -> String {
+        format!("orig-{}.{}", self.hash, self.original_ext)
+    }
+
+    /// File name of one resized variant inside the store
+    pub fn variant_file(&self, width: u32, format: ArtFormat) -> String {
+        format!("w{}-{}.{}", width, self.hash, format.extension())
+    }
+
+    /// Public URL of one resized variant
+    pub fn variant_url(&self, width: u32, format: ArtFormat) -> String {
+        format!("{}/{}", ART_URL_PREFIX, self.variant_file(width, format))
+    }
+
+    /// `srcset` listing every width in one format
+    pub fn srcset(&self, format: ArtFormat) -> String {
+        self.widths
+            .iter()
+            .map(|&w| format!("{} {}w", self.variant_url(w, format), w))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Largest JPEG, for print (720px is ~290dpi at the printed 2.5in)
+    pub fn print_url(&self) -> String {
+        let width = self.widths.last().copied().unwrap_or(self.width);
+        self.variant_url(width, ArtFormat::Jpeg)
+    }
+
+    /// Smallest JPEG, for browsers that ignore `srcset`
+    pub fn fallback_url(&self) -> String {
+        let width = self.widths.first().copied().unwrap_or(self.width);
+        self.variant_url(width, ArtFormat::Jpeg)
+    }
+}
+
+/// Everything `fetch-assets` has mirrored, keyed by the original `art_link`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArtManifest {
+    #[serde(default)]
+    pub images: BTreeMap<String, MirroredArt>,
+}
+
+impl ArtManifest {
+    pub fn get(&self, art_link: &str) -> Option<&MirroredArt> {
+        self.images.get(art_link)
+    }
+}
+
+// Auto-generated by build.rs - embeds assets/art/manifest.yml if it exists
+include!(concat!(env!("OUT_DIR"), "/art_generated.rs"));
+
+/// The manifest this build was compiled against, parsed on first use
+pub fn art_manifest() -> &'static ArtManifest {
+    static MANIFEST: OnceLock<ArtManifest> = OnceLock::new();
+    MANIFEST.get_or_init(|| serde_yaml::from_str(art_manifest_yaml()).unwrap_or_default())
+}
@@ -0,0 +1,128 @@
+//! Downloading and processing album art for the `fetch-assets` binary (native only)
+
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use std::time::Duration;
+
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageEncoder};
+use sha2::{Digest, Sha256};
+
+use crate::art::{ArtFormat, ArtManifest, MirroredArt, VARIANT_WIDTHS};
+
+/// Refuse anything bigger than this, album art is usually well under 1MB
+const MAX_DOWNLOAD_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Name of the manifest inside the store
+pub const MANIFEST_FILE: &str = "manifest.yml";
+
+/// Download an image over HTTP(S)
+pub fn download(url: &str) -> Result<Vec<u8>, String> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(30))
+        .build();
+
+    let response = agent.get(url).call().map_err(|e| e.to_string())?;
+
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_DOWNLOAD_BYTES)
+        .read_to_end(&mut bytes)
+        .map_err(|e| e.to_string())?;
+
+    Ok(bytes)
+}
+
+/// Encode one variant in the given format
+fn encode(image: &DynamicImage, format: ArtFormat) -> Result<Vec<u8>, String> {
+    let rgb = image.to_rgb8();
+    let mut bytes = Vec::new();
+
+    let result = match format {
+        ArtFormat::Jpeg => image::codecs::jpeg::JpegEncoder::new_with_quality(&mut bytes, 85)
+            .write_image(rgb.as_raw(), rgb.width(), rgb.height(), image::ExtendedColorType::Rgb8),
+        ArtFormat::Avif => image::codecs::avif::AvifEncoder::new_with_speed_quality(&mut bytes, 10, 70)
+            .write_image(rgb.as_raw(), rgb.width(), rgb.height(), image::ExtendedColorType::Rgb8),
+    };
+
+    result.map(|_| bytes).map_err(|e| e.to_string())
+}
+
+/// Store the original bytes under their sha256 and write every resized variant next to them
+pub fn mirror(bytes: &[u8], store: &Path) -> Result<MirroredArt, String> {
+    let hash = Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+
+    let format = image::guess_format(bytes).map_err(|e| e.to_string())?;
+    let original_ext = format.extensions_str().first().copied().unwrap_or("bin");
+
+    let image = image::ImageReader::with_format(Cursor::new(bytes), format)
+        .decode()
+        .map_err(|e| e.to_string())?;
+
+    // Never upscale: keep the widths that fit, or just the original size if it's tiny
+    let mut widths: Vec<u32> = VARIANT_WIDTHS
+        .iter()
+        .copied()
+        .filter(|&w| w <= image.width())
+        .collect();
+    if widths.is_empty() {
+        widths.push(image.width());
+    }
+
+    let placeholder = image.resize(32, 32, FilterType::Triangle).to_rgba8();
+    let blurhash = blurhash::encode(4, 3, placeholder.width(), placeholder.height(), placeholder.as_raw())
+        .map_err(|e| e.to_string())?;
+
+    let art = MirroredArt {
+        hash,
+        original_ext: original_ext.to_string(),
+        width: image.width(),
+        height: image.height(),
+        blurhash,
+        widths,
+    };
+
+    fs::create_dir_all(store).map_err(|e| e.to_string())?;
+    fs::write(store.join(art.original_file()), bytes).map_err(|e| e.to_string())?;
+
+    for &width in &art.widths {
+        let resized = image.resize(width, u32::MAX, FilterType::Lanczos3);
+        for format in ArtFormat::ALL {
+            let encoded = encode(&resized, format)?;
+            fs::write(store.join(art.variant_file(width, format)), encoded)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(art)
+}
+
+/// Whether every file a manifest entry points at is present in the store
+pub fn is_complete(art: &MirroredArt, store: &Path) -> bool {
+    store.join(art.original_file()).exists()
+        && art.widths.iter().all(|&w| {
+            ArtFormat::ALL
+                .iter()
+                .all(|&f| store.join(art.variant_file(w, f)).exists())
+        })
+}
+
+/// Read the store's manifest, or start an empty one
+pub fn load_manifest(store: &Path) -> ArtManifest {
+    fs::read_to_string(store.join(MANIFEST_FILE))
+        .ok()
+        .and_then(|yaml| serde_yaml::from_str(&yaml).ok())
+        .unwrap_or_default()
+}
+
+/// Write the store's manifest
+pub fn save_manifest(store: &Path, manifest: &ArtManifest) -> Result<(), String> {
+    let yaml = serde_yaml::to_string(manifest).map_err(|e| e.to_string())?;
+    let header = "# Generated by `cargo run --bin fetch-assets` - do not edit\n";
+    fs::write(store.join(MANIFEST_FILE), format!("{}{}", header, yaml)).map_err(|e| e.to_string())
+}
@@ -0,0 +1,213 @@
+//! Reading track/album metadata out of Bandcamp pages, for the `autofill` binary (native only)
+//!
+//! Bandcamp pages carry two machine-readable blobs: a JSON-LD `<script>` (names,
+//! artist, album, cover art) and the `data-tralbum` attribute (numeric IDs used
+//! by the embedded player). Both are read with plain string scanning so there's
+//! no HTML parser to keep up to date.
+
+use std::io::Read;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::embed::{BandcampEmbed, PlayerArtwork, PlayerSize};
+use crate::form::PoemForm;
+
+/// Bandcamp pages are a few hundred KB, refuse anything absurd
+const MAX_PAGE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Everything a tanka needs from the page its `qr_link` points at
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageInfo {
+    pub track: Option<String>,
+    pub artist: String,
+    pub album: Option<String>,
+    pub art_link: Option<String>,
+    pub album_id: Option<u64>,
+    pub track_id: Option<u64>,
+}
+
+impl PageInfo {
+    /// Player for this page, with the options every existing tanka uses
+    pub fn embed(&self) -> Option<BandcampEmbed> {
+        if self.album_id.is_none() && self.track_id.is_none() {
+            return None;
+        }
+        Some(BandcampEmbed {
+            album: self.album_id,
+            track: self.track_id,
+            size: PlayerSize::Large,
+            bgcol: None,
+            linkcol: None,
+            tracklist: false,
+            artwork: PlayerArtwork::Small,
+        })
+    }
+}
+
+/// Download a Bandcamp page
+pub fn fetch_page(url: &str) -> Result<String, String> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(30))
+        .build();
+
+    let response = agent.get(url).call().map_err(|e| e.to_string())?;
+
+    let mut html = String::new();
+    response
+        .into_reader()
+        .take(MAX_PAGE_BYTES)
+        .read_to_string(&mut html)
+        .map_err(|e| e.to_string())?;
+
+    Ok(html)
+}
+
+/// Pull the pairing metadata out of a track or album page
+pub fn parse_page(html: &str) -> Result<PageInfo, String> {
+    let ld = json_ld(html).ok_or("no JSON-LD metadata found (is this a Bandcamp track/album page?)")?;
+    let tralbum = tralbum(html);
+
+    let ld_type = ld["@type"].as_str().unwrap_or_default();
+    let is_track = match tralbum.as_ref().and_then(|t| t["item_type"].as_str()) {
+        Some(item_type) => item_type == "track",
+        None => ld_type == "MusicRecording",
+    };
+
+    let name = ld["name"].as_str().map(str::to_string);
+    let (track, album) = if is_track {
+        (name, ld["inAlbum"]["name"].as_str().map(str::to_string))
+    } else {
+        (None, name)
+    };
+
+    let artist = ld["byArtist"]["name"]
+        .as_str()
+        .or_else(|| tralbum.as_ref().and_then(|t| t["artist"].as_str()))
+        .ok_or("page has no artist")?
+        .to_string();
+
+    // Prefer the _10 (1200px) rendition built from art_id, like the links already in content/
+    let art_link = tralbum
+        .as_ref()
+        .and_then(|t| t["art_id"].as_u64())
+        .map(|id| format!("https://f4.bcbits.com/img/a{:010}_10.jpg", id))
+        .or_else(|| image_url(&ld["image"]));
+
+    let (album_id, track_id) = match &tralbum {
+        Some(t) if is_track => (t["current"]["album_id"].as_u64(), t["id"].as_u64()),
+        Some(t) => (t["id"].as_u64(), None),
+        None => (None, None),
+    };
+
+    Ok(PageInfo {
+        track,
+        artist,
+        album,
+        art_link,
+        album_id,
+        track_id,
+    })
+}
+
+/// JSON-LD `image` is either a URL or a list of them
+fn image_url(image: &Value) -> Option<String> {
+    match image {
+        Value::String(url) => Some(url.clone()),
+        Value::Array(urls) => urls.iter().find_map(|u| u.as_str()).map(str::to_string),
+        _ => None,
+    }
+}
+
+/// The first `<script type="application/ld+json">` block
+fn json_ld(html: &str) -> Option<Value> {
+    let start = html.find("application/ld+json")?;
+    let rest = &html[start..];
+    let body_start = rest.find('>')? + 1;
+    let body_end = rest.find("</script>")?;
+    serde_json::from_str(rest[body_start..body_end].trim()).ok()
+}
+
+/// The HTML-escaped JSON in `data-tralbum="..."`
+fn tralbum(html: &str) -> Option<Value> {
+    const ATTR: &str = "data-tralbum=\"";
+    let start = html.find(ATTR)? + ATTR.len();
+    let end = start + html[start..].find('"')?;
+    serde_json::from_str(&unescape_html(&html[start..end])).ok()
+}
+
+/// Undo the entity escaping Bandcamp applies to attribute values
+fn unescape_html(s: &str) -> String {
+    s.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// A YAML double-quoted scalar (JSON strings are valid YAML)
+fn yaml_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// The file a tanka called `name` is written to inside `content/`: its slug,
+/// so the page lands at `/tanka/<slug>`. Names that would leave `content/`
+/// (or that slug to nothing) are refused.
+pub fn tanka_file_name(name: &str) -> Result<String, String> {
+    let slug = crate::model::slugify(name.trim());
+    if slug.is_empty() || slug.starts_with('.') || slug.contains(['/', '\\']) {
+        return Err(format!("{:?} can't be a file name in content/", name));
+    }
+    Ok(format!("{}.tanka.yml", slug))
+}
+
+/// A new tanka file (or another form's) with everything from the page filled in
+/// and placeholders for the rest
+pub fn tanka_yaml(name: &str, form: PoemForm, qr_link: &str, info: &PageInfo) -> String {
+    let mut yaml = String::new();
+
+    let flavor = match form.pattern() {
+        Some(pattern) => format!("{} // {} // [ {} ] ", form.label(), pattern, name),
+        None => format!("{} // [ {} ] ", form.label(), name),
+    };
+    yaml.push_str(&format!("top_flavor: {}\n", yaml_string(&flavor)));
+    yaml.push_str(&format!("qr_link: {}\n", yaml_string(qr_link)));
+    match &info.art_link {
+        Some(art) => yaml.push_str(&format!("art_link: {}\n", yaml_string(art))),
+        None => yaml.push_str("art_link: null\n"),
+    }
+    match info.embed() {
+        Some(embed) => {
+            yaml.push_str("bandcamp_embed:\n");
+            if let Some(album) = embed.album {
+                yaml.push_str(&format!("  album: {}\n", album));
+            }
+            if let Some(track) = embed.track {
+                yaml.push_str(&format!("  track: {}\n", track));
+            }
+        }
+        None => yaml.push_str("bandcamp_embed_isprivate: true\n"),
+    }
+
+    yaml.push_str("recommended_music_pairing:\n");
+    let track = info.track.as_deref().unwrap_or("full album");
+    yaml.push_str(&format!("  track: {}\n", yaml_string(track)));
+    yaml.push_str(&format!("  artist: {}\n", yaml_string(&info.artist)));
+    let album = info.album.as_deref().unwrap_or(track);
+    yaml.push_str(&format!("  album: {}\n", yaml_string(album)));
+    yaml.push_str("  volume_level: \"comfortable\"\n");
+
+    if form == PoemForm::Tanka {
+        yaml.push_str("tanka:\n");
+    } else {
+        yaml.push_str(&format!("form: {}\npoem:\n", form.name()));
+    }
+    for n in 1..=form.line_count().unwrap_or(3) {
+        yaml.push_str(&format!("  {}: \"verse {}\"\n", n, n));
+    }
+    yaml.push_str("tankadesc: \"about the tanka\"\n");
+    yaml.push_str("tastingnotes: \"about the song\"\n");
+    yaml.push_str("tags: []\n");
+
+    yaml
+}
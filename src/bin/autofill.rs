@@ -0,0 +1,120 @@
+//! Start a new tanka from a Bandcamp link
+//!
+//! Usage: autofill <qr_link> --name <tanka name> [--form haiku] [--html saved-page.html] [--stdout] [--force]
+//!
+//! Fetches the page (or reads a saved copy with `--html`), fills in the music
+//! pairing, art link and embed, and writes `content/<slug of name>.tanka.yml` with
+//! placeholder verses for the form (tanka unless `--form` says otherwise).
+//! Existing files are left alone unless `--force` is given.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use digital_khole::bandcamp::{fetch_page, parse_page, tanka_file_name, tanka_yaml};
+use digital_khole::form::PoemForm;
+
+const USAGE: &str =
+    "usage: autofill <qr_link> --name <tanka name> [--form haiku] [--html saved-page.html] [--stdout] [--force]";
+
+fn main() -> ExitCode {
+    let mut qr_link = None;
+    let mut name = None;
+    let mut form = PoemForm::default();
+    let mut saved_page = None;
+    let mut stdout = false;
+    let mut force = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--name" => match args.next() {
+                Some(value) => name = Some(value),
+                None => {
+                    eprintln!("error: missing value for --name");
+                    return ExitCode::FAILURE;
+                }
+            },
+            "--form" => match args.next() {
+                Some(value) => match PoemForm::from_name(&value) {
+                    Some(value) => form = value,
+                    None => {
+                        let names: Vec<_> = PoemForm::ALL.iter().map(|f| f.name()).collect();
+                        eprintln!("error: unknown form {:?}, expected one of {}", value, names.join("/"));
+                        return ExitCode::FAILURE;
+                    }
+                },
+                None => {
+                    eprintln!("error: missing value for --form");
+                    return ExitCode::FAILURE;
+                }
+            },
+            "--html" => match args.next() {
+                Some(path) => saved_page = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("error: missing value for --html");
+                    return ExitCode::FAILURE;
+                }
+            },
+            "--stdout" => stdout = true,
+            "--force" => force = true,
+            _ if !arg.starts_with("--") && qr_link.is_none() => qr_link = Some(arg),
+            _ => {
+                eprintln!("error: unknown argument: {}", arg);
+                eprintln!("{}", USAGE);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let (Some(qr_link), Some(name)) = (qr_link, name) else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+    let file_name = match tanka_file_name(&name) {
+        Ok(file_name) => file_name,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let html = match &saved_page {
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e)),
+        None => fetch_page(&qr_link),
+    };
+
+    let info = match html.and_then(|html| parse_page(&html)) {
+        Ok(info) => info,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let yaml = tanka_yaml(&name, form, &qr_link, &info);
+
+    if stdout {
+        print!("{}", yaml);
+        return ExitCode::SUCCESS;
+    }
+
+    let path = PathBuf::from("content").join(file_name);
+    if path.exists() && !force {
+        eprintln!("error: {} already exists (use --force to overwrite)", path.display());
+        return ExitCode::FAILURE;
+    }
+
+    if let Err(e) = fs::write(&path, yaml) {
+        eprintln!("error writing {}: {}", path.display(), e);
+        return ExitCode::FAILURE;
+    }
+
+    println!("wrote {}", path.display());
+    if info.embed().is_none() {
+        println!("note: no album/track IDs found, marked bandcamp_embed_isprivate");
+    }
+    println!("next: write the verses, tankadesc and volume_level, then `just validate`");
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,104 @@
+//! Mirror every `art_link` in content/ into the local content-addressed art store
+//!
+//! Usage: fetch-assets [--store assets/art] [--force]
+//!
+//! Images already in the manifest (with all their files present) are skipped
+//! unless `--force` is given.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use digital_khole::art_fetch::{download, is_complete, load_manifest, mirror, save_manifest};
+use digital_khole::Tanka;
+
+fn main() -> ExitCode {
+    let mut store = PathBuf::from("assets/art");
+    let mut force = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--store" => match args.next() {
+                Some(dir) => store = PathBuf::from(dir),
+                None => {
+                    eprintln!("error: missing value for --store");
+                    return ExitCode::FAILURE;
+                }
+            },
+            "--force" => force = true,
+            _ => {
+                eprintln!("error: unknown argument: {}", arg);
+                eprintln!("usage: fetch-assets [--store assets/art] [--force]");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let content_dir = Path::new("content");
+    let entries = match fs::read_dir(content_dir) {
+        Ok(e) => e,
+        Err(e) => {
+            eprintln!("error reading content/: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Every distinct art_link, in a stable order
+    let mut links: Vec<String> = entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            !name.contains("template") && name.ends_with(".yml")
+        })
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|yaml| serde_yaml::from_str::<Tanka>(&yaml).ok())
+        .flat_map(|tanka| tanka.pairings())
+        .filter_map(|pairing| pairing.art_link)
+        .filter(|link| link.starts_with("http://") || link.starts_with("https://"))
+        .collect();
+    links.sort();
+    links.dedup();
+
+    let mut manifest = load_manifest(&store);
+    let mut fetched = 0;
+    let mut cached = 0;
+    let mut failed = 0;
+
+    for link in &links {
+        if !force && manifest.get(link).is_some_and(|art| is_complete(art, &store)) {
+            println!("CACHED {}", link);
+            cached += 1;
+            continue;
+        }
+
+        match download(link).and_then(|bytes| mirror(&bytes, &store)) {
+            Ok(art) => {
+                println!("OK     {} -> {}", link, art.original_file());
+                manifest.images.insert(link.clone(), art);
+                fetched += 1;
+            }
+            Err(e) => {
+                eprintln!("FAIL   {}: {}", link, e);
+                failed += 1;
+            }
+        }
+    }
+
+    // Forget images no tanka points at any more (their files stay until cleaned)
+    manifest.images.retain(|link, _| links.contains(link));
+
+    if let Err(e) = save_manifest(&store, &manifest) {
+        eprintln!("error writing manifest: {}", e);
+        return ExitCode::FAILURE;
+    }
+
+    println!();
+    println!("{} fetched, {} cached, {} failed", fetched, cached, failed);
+
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,344 @@
+//! Production static server for the prebuilt `dist/` directory
+//!
+//! Replaces `trunk serve` (a dev server with file watching) in production.
+//!
+//! Usage: serve [--address 127.0.0.1] [--port 3004] [--dist dist] [--base-url https://example.com]
+//!
+//! `/rss.xml` is only served with `--base-url`: feed readers keep its links,
+//! and the request's `Host` header is whatever the client says it is.
+//!
+//! Built with `--features ssr`, pages are rendered to HTML on the server and
+//! hydrated in the browser instead of being served as an empty SPA shell.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use axum::extract::{Path as UrlPath, Request, State};
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::routing::get;
+use axum::Router;
+use tower_http::services::ServeDir;
+
+use digital_khole::all_tankas;
+use digital_khole::csp::{inline_script_hashes, ContentSources};
+use digital_khole::dist::{cache_control, precompress};
+use digital_khole::feed::{rss, RSS_PATH};
+use digital_khole::pick::{daily, today};
+
+struct Config {
+    address: String,
+    port: u16,
+    dist: PathBuf,
+    base_url: Option<String>,
+}
+
+#[derive(Clone)]
+struct AppState {
+    #[cfg(not(feature = "ssr"))]
+    dist: PathBuf,
+    #[cfg(feature = "ssr")]
+    shell: Arc<str>,
+    slugs: Arc<HashSet<String>>,
+    base_url: Option<Arc<str>>,
+    content_security_policy: HeaderValue,
+    permissions_policy: HeaderValue,
+}
+
+/// Parse `--flag value` style arguments, matching the flags trunk serve took
+fn parse_args() -> Result<Config, String> {
+    let mut config = Config {
+        address: "127.0.0.1".to_string(),
+        port: 3004,
+        dist: PathBuf::from("dist"),
+        base_url: None,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {}", flag))?;
+
+        match flag.as_str() {
+            "--address" => config.address = value,
+            "--port" => {
+                config.port = value
+                    .parse()
+                    .map_err(|_| format!("invalid port: {}", value))?
+            }
+            "--dist" => config.dist = PathBuf::from(value),
+            "--base-url" => {
+                if !value.starts_with("https://") && !value.starts_with("http://") {
+                    return Err(format!("--base-url must be the site's absolute URL, not {:?}", value));
+                }
+                config.base_url = Some(value)
+            }
+            _ => return Err(format!("unknown argument: {}", flag)),
+        }
+    }
+
+    Ok(config)
+}
+
+/// Serve the SPA shell with the given status (the client router takes it from there)
+#[cfg(not(feature = "ssr"))]
+async fn serve_page(state: &AppState, status: StatusCode, req: Request) -> Response {
+    use tower::ServiceExt;
+    use tower_http::services::ServeFile;
+
+    let index = ServeFile::new(state.dist.join("index.html"))
+        .precompressed_br()
+        .precompressed_gzip();
+
+    let mut res = index.oneshot(req).await.into_response();
+    if res.status() == StatusCode::OK {
+        *res.status_mut() = status;
+    }
+    res
+}
+
+/// Server-render the requested page into the shell with the given status
+#[cfg(feature = "ssr")]
+async fn serve_page(state: &AppState, status: StatusCode, req: Request) -> Response {
+    // The query too: a verse link's `?v=` decides its card
+    let url = req.uri().path_and_query().map_or("/", |pq| pq.as_str());
+    let html = digital_khole::ssr::render_page(&state.shell, url).await;
+    (status, axum::response::Html(html)).into_response()
+}
+
+async fn index(State(state): State<AppState>, req: Request) -> Response {
+    serve_page(&state, StatusCode::OK, req).await
+}
+
+/// SPA fallback for `/tanka/:slug` (and its print view), with a real 404 for slugs that don't exist
+async fn tanka(
+    State(state): State<AppState>,
+    UrlPath(slug): UrlPath<String>,
+    req: Request,
+) -> Response {
+    let status = if state.slugs.contains(&slug) {
+        StatusCode::OK
+    } else {
+        StatusCode::NOT_FOUND
+    };
+    serve_page(&state, status, req).await
+}
+
+/// SPA fallback for `/fragment/:name`, with a real 404 for fragments the book doesn't read
+async fn fragment(
+    State(state): State<AppState>,
+    UrlPath(name): UrlPath<String>,
+    req: Request,
+) -> Response {
+    let status = if digital_khole::book::fragment(&name).is_some() {
+        StatusCode::OK
+    } else {
+        StatusCode::NOT_FOUND
+    };
+    serve_page(&state, status, req).await
+}
+
+/// SPA fallback for `/tag/:name`, with a real 404 for tags that aren't in tags.yml
+async fn tag(
+    State(state): State<AppState>,
+    UrlPath(name): UrlPath<String>,
+    req: Request,
+) -> Response {
+    let status = if digital_khole::tags::tag(&name).is_some() {
+        StatusCode::OK
+    } else {
+        StatusCode::NOT_FOUND
+    };
+    serve_page(&state, status, req).await
+}
+
+/// The day's tanka: the same for everyone on a given UTC date
+async fn today_redirect() -> Response {
+    match daily(&all_tankas(), today()) {
+        Some(entry) => Redirect::to(&format!("/tanka/{}", entry.slug)).into_response(),
+        None => Redirect::to("/").into_response(),
+    }
+}
+
+/// RSS for the whole book, led by the day's pick (a 404 without `--base-url`)
+async fn rss_feed(State(state): State<AppState>) -> Response {
+    let Some(base_url) = &state.base_url else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    (
+        [(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")],
+        rss(&all_tankas(), base_url, today()),
+    )
+        .into_response()
+}
+
+async fn healthz() -> &'static str {
+    "ok"
+}
+
+/// Hashed assets are cached forever, everything else must revalidate
+async fn cache_headers(req: Request, next: Next) -> Response {
+    let file_name = req
+        .uri()
+        .path()
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+
+    let mut res = next.run(req).await;
+
+    let value = cache_control(&file_name, res.status().is_success());
+    res.headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static(value));
+    res
+}
+
+/// Hardening headers sent with every response
+async fn security_headers(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let mut res = next.run(req).await;
+    let headers = res.headers_mut();
+
+    headers.insert(
+        header::CONTENT_SECURITY_POLICY,
+        state.content_security_policy.clone(),
+    );
+    headers.insert("permissions-policy", state.permissions_policy.clone());
+    headers.insert(
+        header::REFERRER_POLICY,
+        HeaderValue::from_static("strict-origin-when-cross-origin"),
+    );
+    headers.insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+    res
+}
+
+/// Resolve on SIGTERM (systemd stop) or Ctrl-C
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sig) => {
+                sig.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    println!("shutting down, draining connections");
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let config = match parse_args() {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            eprintln!("usage: serve [--address 127.0.0.1] [--port 3004] [--dist dist]");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let index_html = match fs::read_to_string(config.dist.join("index.html")) {
+        Ok(html) => html,
+        Err(e) => {
+            eprintln!(
+                "error: {}/index.html: {} (run `just release` first)",
+                config.dist.display(),
+                e
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+
+    #[cfg(feature = "ssr")]
+    let index_html = digital_khole::ssr::prepare_shell(&index_html);
+
+    match precompress(&config.dist) {
+        Ok(n) => println!("precompressed {} files", n),
+        Err(e) => eprintln!("warning: precompression failed: {}", e),
+    }
+
+    let tankas = all_tankas();
+    let sources = ContentSources::from_tankas(tankas.iter().map(|t| &t.tanka));
+    let csp = sources.content_security_policy(&inline_script_hashes(&index_html));
+    println!("content-security-policy: {}", csp);
+    if config.base_url.is_none() {
+        eprintln!("warning: no --base-url, so {} is a 404", RSS_PATH);
+    }
+
+    let state = AppState {
+        #[cfg(not(feature = "ssr"))]
+        dist: config.dist.clone(),
+        #[cfg(feature = "ssr")]
+        shell: Arc::from(index_html.as_str()),
+        slugs: Arc::new(tankas.into_iter().map(|t| t.slug).collect()),
+        base_url: config.base_url.as_deref().map(Arc::from),
+        content_security_policy: HeaderValue::from_str(&csp).expect("CSP is a valid header value"),
+        permissions_policy: HeaderValue::from_str(&sources.permissions_policy())
+            .expect("Permissions-Policy is a valid header value"),
+    };
+
+    let assets = ServeDir::new(&config.dist)
+        .precompressed_br()
+        .precompressed_gzip();
+
+    let app = Router::new()
+        .route("/", get(index))
+        .route("/healthz", get(healthz))
+        .route("/tanka/{slug}", get(tanka))
+        .route("/tanka/{slug}/print", get(tanka))
+        .route("/fragment/{name}", get(fragment))
+        .route("/tags", get(index))
+        .route("/random", get(index))
+        .route("/today", get(today_redirect))
+        .route(RSS_PATH, get(rss_feed))
+        .route("/tag/{name}", get(tag))
+        .route("/print/all", get(index))
+        .fallback_service(assets)
+        .layer(middleware::from_fn(cache_headers))
+        .layer(middleware::from_fn_with_state(state.clone(), security_headers))
+        .with_state(state);
+
+    let addr = format!("{}:{}", config.address, config.port);
+    let listener = match tokio::net::TcpListener::bind(&addr).await {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("error: could not bind {}: {}", addr, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!("serving {} on http://{}", config.dist.display(), addr);
+
+    if let Err(e) = axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await
+    {
+        eprintln!("error: {}", e);
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
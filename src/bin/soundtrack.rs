@@ -0,0 +1,69 @@
+//! Write every music pairing as M3U, XSPF and JSPF playlists
+//!
+//! Usage: soundtrack --base-url https://example.com [--out dist]
+//!
+//! Run after `trunk build` so the index page's "download the soundtrack"
+//! links resolve (`just build` / `just release` do this). The base URL is
+//! required: the playlists are for downloading, and links into the site have
+//! to keep working once they're saved somewhere else.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use digital_khole::all_tankas;
+use digital_khole::soundtrack::{soundtrack, to_jspf, to_m3u, to_xspf, JSPF_FILE, M3U_FILE, XSPF_FILE};
+
+const USAGE: &str = "usage: soundtrack --base-url https://example.com [--out dist]";
+
+fn main() -> ExitCode {
+    let mut out = PathBuf::from("dist");
+    let mut base_url = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let Some(value) = args.next() else {
+            eprintln!("error: missing value for {}", flag);
+            return ExitCode::FAILURE;
+        };
+
+        match flag.as_str() {
+            "--out" => out = PathBuf::from(value),
+            "--base-url" => base_url = Some(value),
+            _ => {
+                eprintln!("error: unknown argument: {}", flag);
+                eprintln!("{}", USAGE);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let Some(base_url) = base_url.filter(|url| url.starts_with("https://") || url.starts_with("http://")) else {
+        eprintln!("error: --base-url must be the site's absolute URL (set SITE_URL for `just release`)");
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let tracks = soundtrack(&all_tankas(), &base_url);
+
+    if let Err(e) = fs::create_dir_all(&out) {
+        eprintln!("error: {}: {}", out.display(), e);
+        return ExitCode::FAILURE;
+    }
+
+    for (file, contents) in [
+        (M3U_FILE, to_m3u(&tracks)),
+        (XSPF_FILE, to_xspf(&tracks)),
+        (JSPF_FILE, to_jspf(&tracks)),
+    ] {
+        let path = out.join(file);
+        if let Err(e) = fs::write(&path, contents) {
+            eprintln!("error writing {}: {}", path.display(), e);
+            return ExitCode::FAILURE;
+        }
+        println!("wrote {}", path.display());
+    }
+
+    println!("{} tracks", tracks.len());
+    ExitCode::SUCCESS
+}
@@ -1,48 +1,16 @@
-#![allow(dead_code)]
-
 use std::fs;
 use std::path::Path;
 use std::process::ExitCode;
 
-use serde::Deserialize;
-
-#[derive(Debug, Deserialize)]
-struct Tanka {
-    top_flavor: String,
-    qr_link: String,
-    art_link: String,
-    #[serde(default)]
-    bandcamp_embed: Option<String>,
-    #[serde(default)]
-    bandcamp_embed_isprivate: bool,
-    recommended_music_pairing: MusicPairing,
-    tanka: TankaVerses,
-    tankadesc: String,
-    #[serde(default)]
-    tastingnotes: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct MusicPairing {
-    track: String,
-    artist: String,
-    album: String,
-    volume_level: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct TankaVerses {
-    #[serde(rename = "1")]
-    v1: String,
-    #[serde(rename = "2")]
-    v2: String,
-    #[serde(rename = "3")]
-    v3: String,
-    #[serde(rename = "4")]
-    v4: String,
-    #[serde(rename = "5")]
-    v5: String,
-}
+use digital_khole::book::Book;
+use digital_khole::csp::ContentSources;
+use digital_khole::embed::{link_problems, AUDIO_URL_PREFIX};
+use digital_khole::i18n::{message_ids, Locale, MESSAGES};
+use digital_khole::form::PoemForm;
+use digital_khole::japanese::{count_morae, parse_ruby};
+use digital_khole::tags::{is_well_formed, near_duplicates, parse_vocabulary, suggest, Tag};
+use digital_khole::volume::VolumeLevel;
+use digital_khole::{slugify, translation_of, Tanka, TankaTranslation};
 
 fn main() -> ExitCode {
     let content_dir = Path::new("content");
@@ -55,6 +23,8 @@ fn main() -> ExitCode {
     let mut found = 0;
     let mut passed = 0;
     let mut failed = 0;
+    let mut sources = ContentSources::default();
+    let vocabulary = parse_vocabulary(&fs::read_to_string("tags.yml").unwrap_or_default()).unwrap_or_default();
 
     let entries = match fs::read_dir(content_dir) {
         Ok(e) => e,
@@ -84,10 +54,87 @@ fn main() -> ExitCode {
             }
         };
 
-        match serde_yaml::from_str::<Tanka>(&content) {
-            Ok(_) => {
-                println!("OK   {}", name);
+        if let Some((original, lang)) = translation_of(&name) {
+            if check_translation(&name, &original, lang, &content) {
                 passed += 1;
+            } else {
+                failed += 1;
+            }
+            continue;
+        }
+
+        match serde_yaml::from_str::<Tanka>(&content) {
+            Ok(tanka) => {
+                let mut problems = Vec::new();
+                // Reported but not failed: jiamari/jitarazu are part of the form
+                let mut warnings = Vec::new();
+
+                // Malformed embeds already fail to parse; this catches contradictory ones
+                if tanka.bandcamp_embed.is_some() && tanka.bandcamp_embed_isprivate {
+                    problems.push("bandcamp_embed is set but bandcamp_embed_isprivate hides it".to_string());
+                }
+
+                let is_setlist = tanka.recommended_music_pairing.len() > 1;
+                for (i, (pairing, resolved)) in
+                    tanka.recommended_music_pairing.iter().zip(tanka.pairings()).enumerate()
+                {
+                    let prefix = if is_setlist {
+                        format!("recommended_music_pairing[{}]", i)
+                    } else {
+                        "recommended_music_pairing".to_string()
+                    };
+
+                    if pairing.bandcamp_embed.is_some() && pairing.bandcamp_embed_isprivate {
+                        problems.push(format!("{}: bandcamp_embed is set but bandcamp_embed_isprivate hides it", prefix));
+                    }
+
+                    if let Some(VolumeLevel::Custom { text, meter: None }) = &pairing.volume_level {
+                        problems.push(format!(
+                            "{}.volume_level: unknown phrasing {:?}, use one of {} or {{ custom: {:?}, meter: 1-5 }}",
+                            prefix,
+                            text,
+                            VolumeLevel::SCALE.map(|v| v.label().to_string()).join("/"),
+                            text
+                        ));
+                    }
+
+                    for (field, problem) in link_problems(&resolved) {
+                        problems.push(format!("{}.{}: {}", prefix, field, problem));
+                    }
+
+                    // Self-hosted audio has to actually be in the store trunk copies
+                    if let Some(file) = resolved.audio.as_deref().and_then(|a| a.strip_prefix(AUDIO_URL_PREFIX)) {
+                        if !Path::new("assets/audio").join(file).exists() {
+                            problems.push(format!("{}: assets/audio/{} does not exist", prefix, file));
+                        }
+                    }
+                }
+
+                check_form(&tanka, &mut problems);
+                check_tags(&tanka, &vocabulary, &mut problems);
+
+                if tanka.tanka.has_japanese() {
+                    check_japanese(&tanka, &mut problems, &mut warnings);
+                }
+
+                // Anything the served Content-Security-Policy would refuse to load
+                for b in sources.add(&tanka) {
+                    problems.push(format!("{} {} blocked by CSP: {}", b.field, b.url, b.reason));
+                }
+
+                for warning in warnings {
+                    eprintln!("WARN {}: {}", name, warning);
+                }
+
+                if problems.is_empty() {
+                    println!("OK   {}", name);
+                    passed += 1;
+                } else {
+                    for problem in problems {
+                        eprintln!("FAIL {}: {}", name, problem);
+                    }
+                    failed += 1;
+                }
             }
             Err(e) => {
                 eprintln!("FAIL {}: {}", name, e);
@@ -96,7 +143,29 @@ fn main() -> ExitCode {
         }
     }
 
+    let (locales_passed, locales_failed) = check_locales();
+    passed += locales_passed;
+    failed += locales_failed;
+    found += locales_passed + locales_failed;
+
+    found += 1;
+    if check_vocabulary(content_dir) {
+        passed += 1;
+    } else {
+        failed += 1;
+    }
+
+    found += 1;
+    if check_book(content_dir) {
+        passed += 1;
+    } else {
+        failed += 1;
+    }
+
     println!();
+    println!("frame-src allowlist: {:?}", sources.frame_origins);
+    println!("img-src allowlist:   {:?}", sources.img_origins);
+    println!("media-src allowlist: {:?}", sources.media_origins);
     println!("{} found, {} passed, {} failed", found, passed, failed);
 
     if failed > 0 {
@@ -105,3 +174,272 @@ fn main() -> ExitCode {
         ExitCode::SUCCESS
     }
 }
+
+/// Fixed forms need their number of lines, and a `top_flavor` pattern that
+/// matches the form (a haiku isn't "57577")
+fn check_form(tanka: &Tanka, problems: &mut Vec<String>) {
+    let form = tanka.form;
+    let lines = tanka.tanka.lines().len();
+
+    if let Some(expected) = form.line_count() {
+        if lines != expected {
+            problems.push(format!("a {} has {} lines, this has {}", form, expected, lines));
+        }
+        if tanka.tanka.lines().iter().any(|v| v.text.trim().is_empty()) {
+            problems.push(format!("blank line in a {} (only free verse has stanza breaks)", form));
+        }
+    }
+
+    // The first run of digits, e.g. "57577" in "tanka // 57577 // [ mom ]"
+    let digits: String = tanka
+        .top_flavor
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(char::is_ascii_digit)
+        .collect();
+    if digits.len() > 1 {
+        match form.pattern() {
+            Some(pattern) if digits != pattern => problems.push(format!(
+                "top_flavor says {}, but a {} is {}",
+                digits, form, pattern
+            )),
+            None => {
+                let named = PoemForm::ALL.into_iter().find(|f| f.pattern().as_deref() == Some(digits.as_str()));
+                if let Some(named) = named {
+                    problems.push(format!("top_flavor says {} (a {}), but form is {}", digits, named, form));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Ruby markup has to parse; the mora count (5-7-5-7-7 for a tanka) is only a warning
+fn check_japanese(tanka: &Tanka, problems: &mut Vec<String>, warnings: &mut Vec<String>) {
+    let mut counts = Vec::new();
+
+    for (i, verse) in tanka.tanka.lines().iter().enumerate() {
+        // Stanza breaks in free verse
+        if verse.text.trim().is_empty() {
+            continue;
+        }
+        let Some(ja) = &verse.ja else {
+            warnings.push(format!("tanka.{}: no ja, while other verses have one", i + 1));
+            return;
+        };
+        match parse_ruby(ja) {
+            Ok(segments) => match count_morae(&segments) {
+                Ok(count) => counts.push(count),
+                Err(e) => {
+                    warnings.push(format!("tanka.{}.ja: {}", i + 1, e));
+                    return;
+                }
+            },
+            Err(e) => {
+                problems.push(format!("tanka.{}.ja: {}", i + 1, e));
+                return;
+            }
+        }
+    }
+
+    // Free verse has no count to keep
+    let Some(expected) = tanka.form.syllables() else { return };
+    if counts != expected {
+        let show = |c: &[usize]| c.iter().map(usize::to_string).collect::<Vec<_>>().join("-");
+        warnings.push(format!("ja morae are {}, expected {}", show(&counts), show(expected)));
+    }
+}
+
+/// A `<name>.tanka.<lang>.yml` needs its tanka and a language the site speaks.
+/// Commentary it leaves untranslated shows in the original, so that's a warning.
+fn check_translation(name: &str, original: &str, lang: &str, content: &str) -> bool {
+    let mut problems = Vec::new();
+
+    if Locale::from_code(lang).is_none() {
+        let codes: Vec<_> = Locale::ALL.iter().map(|l| l.code()).collect();
+        problems.push(format!("no {:?} locale, expected one of {}", lang, codes.join("/")));
+    }
+
+    let tanka = match fs::read_to_string(Path::new("content").join(original)) {
+        Ok(yaml) => serde_yaml::from_str::<Tanka>(&yaml).ok(),
+        Err(_) => {
+            problems.push(format!("translates {}, which does not exist", original));
+            None
+        }
+    };
+
+    match serde_yaml::from_str::<TankaTranslation>(content) {
+        Ok(translation) => {
+            if let Some(tanka) = tanka {
+                if translation.tankadesc.is_none() {
+                    eprintln!("WARN {}: tankadesc not translated", name);
+                }
+                if tanka.tastingnotes.is_some() && translation.tastingnotes.is_none() {
+                    eprintln!("WARN {}: tastingnotes not translated", name);
+                }
+                if tanka.tastingnotes.is_none() && translation.tastingnotes.is_some() {
+                    eprintln!("WARN {}: tastingnotes translated, but {} has none", name, original);
+                }
+            }
+        }
+        Err(e) => problems.push(e.to_string()),
+    }
+
+    if problems.is_empty() {
+        println!("OK   {}", name);
+        true
+    } else {
+        for problem in problems {
+            eprintln!("FAIL {}: {}", name, problem);
+        }
+        false
+    }
+}
+
+/// Tags come from `tags.yml`, once each
+fn check_tags(tanka: &Tanka, vocabulary: &[Tag], problems: &mut Vec<String>) {
+    for (i, name) in tanka.tags.iter().enumerate() {
+        if tanka.tags[..i].contains(name) {
+            problems.push(format!("tags: {} is listed twice", name));
+        } else if !vocabulary.iter().any(|t| t.name == *name) {
+            let hint = suggest(name, vocabulary).map(|s| format!(" (did you mean {}?)", s)).unwrap_or_default();
+            problems.push(format!("tags: {} isn't in tags.yml{}", name, hint));
+        }
+    }
+}
+
+/// `tags.yml` has to parse, with well-formed names and no two that are the
+/// same tag spelled differently. Tags no tanka uses are only reported.
+fn check_vocabulary(content_dir: &Path) -> bool {
+    let vocabulary = match fs::read_to_string("tags.yml").map_err(|e| e.to_string()).and_then(|y| parse_vocabulary(&y)) {
+        Ok(vocabulary) => vocabulary,
+        Err(e) => {
+            eprintln!("FAIL tags.yml: {}", e);
+            return false;
+        }
+    };
+
+    let mut problems = Vec::new();
+    for (i, tag) in vocabulary.iter().enumerate() {
+        if !is_well_formed(&tag.name) {
+            problems.push(format!("{:?}: use lowercase words joined by hyphens", tag.name));
+        }
+        for other in &vocabulary[..i] {
+            if near_duplicates(&tag.name, &other.name) {
+                problems.push(format!("{} and {} look like the same tag", other.name, tag.name));
+            }
+        }
+    }
+
+    let used: Vec<String> = fs::read_dir(content_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| !e.file_name().to_string_lossy().contains("template"))
+        .filter_map(|e| fs::read_to_string(e.path()).ok())
+        .filter_map(|yaml| serde_yaml::from_str::<Tanka>(&yaml).ok())
+        .flat_map(|tanka| tanka.tags)
+        .collect();
+    let unused: Vec<&str> = vocabulary
+        .iter()
+        .map(|t| t.name.as_str())
+        .filter(|name| !used.iter().any(|u| u == name))
+        .collect();
+    if !unused.is_empty() {
+        eprintln!("WARN tags.yml: no tanka uses {}", unused.join(", "));
+    }
+
+    if problems.is_empty() {
+        println!("OK   tags.yml");
+        true
+    } else {
+        for problem in problems {
+            eprintln!("FAIL tags.yml: {}", problem);
+        }
+        false
+    }
+}
+
+/// `book.yml` has to parse, list only tankas and fragments that exist, list
+/// each once, and leave no tanka out
+fn check_book(content_dir: &Path) -> bool {
+    let mut slugs = Vec::new();
+    let mut fragments = Vec::new();
+    for entry in fs::read_dir(content_dir).into_iter().flatten().flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some(fragment) = name.strip_suffix(".fragmentedpoem") {
+            fragments.push(fragment.to_string());
+        } else if name.ends_with(".yml") && !name.contains("template") && translation_of(&name).is_none() {
+            slugs.push(slugify(&name));
+        }
+    }
+    slugs.sort();
+
+    let problems = match fs::read_to_string("book.yml").map_err(|e| e.to_string()).and_then(|yaml| Book::parse(&yaml)) {
+        Ok(book) => book.problems(&slugs, &fragments),
+        Err(e) => vec![e],
+    };
+
+    if problems.is_empty() {
+        println!("OK   book.yml");
+        true
+    } else {
+        for problem in problems {
+            eprintln!("FAIL book.yml: {}", problem);
+        }
+        false
+    }
+}
+
+/// Every locale in `locales/` has to parse and carry every message the UI asks for.
+/// Returns `(passed, failed)`.
+fn check_locales() -> (usize, usize) {
+    let (mut passed, mut failed) = (0, 0);
+
+    let mut files: Vec<String> = fs::read_dir("locales")
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|name| name.ends_with(".ftl"))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+
+    for file in files {
+        let name = format!("locales/{}", file);
+        let mut problems = Vec::new();
+
+        let code = file.trim_end_matches(".ftl");
+        if Locale::from_code(code).is_none() {
+            problems.push(format!("{:?} isn't in Locale::ALL, so it is never shown", code));
+        }
+
+        match message_ids(&fs::read_to_string(&name).unwrap_or_default()) {
+            Ok(ids) => {
+                let missing: Vec<_> = MESSAGES.iter().filter(|id| !ids.iter().any(|i| i == *id)).copied().collect();
+                if !missing.is_empty() {
+                    problems.push(format!("missing {}", missing.join(", ")));
+                }
+                let unused: Vec<_> = ids.iter().filter(|id| !MESSAGES.contains(&id.as_str())).cloned().collect();
+                if !unused.is_empty() {
+                    eprintln!("WARN {}: not in i18n::MESSAGES, never shown: {}", name, unused.join(", "));
+                }
+            }
+            Err(e) => problems.push(e),
+        }
+
+        if problems.is_empty() {
+            println!("OK   {}", name);
+            passed += 1;
+        } else {
+            for problem in problems {
+                eprintln!("FAIL {}: {}", name, problem);
+            }
+            failed += 1;
+        }
+    }
+
+    (passed, failed)
+}
@@ -0,0 +1,66 @@
+//! The book's running order: chapters, sections and their entries from `book.yml`
+//!
+//! `book.yml` decides the order of the index, prev/next, the soundtrack and
+//! the printed zine. Tankas it leaves out still show, after everything it
+//! lists; `just validate` reports them, and entries that don't exist.
+
+mod model;
+
+pub use model::{is_fragment_name, Book, BookEntry, Chapter, Section};
+
+/// `book.yml`, compiled in. A broken file reads as an empty book (every tanka
+/// then shows in file name order); the validator says why.
+pub fn book() -> Book {
+    Book::parse(include_str!("../book.yml")).unwrap_or_default()
+}
+
+/// The text of a fragment the book reads
+pub fn fragment(name: &str) -> Option<&'static str> {
+    crate::all_fragments()
+        .into_iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, text)| text)
+}
+
+/// The book as readers page through it: what `book.yml` lists that exists,
+/// then the tankas it leaves out
+pub fn reading_order() -> Vec<BookEntry> {
+    let tankas = crate::all_tankas();
+    let mut order: Vec<BookEntry> = Vec::new();
+
+    for entry in book().entries() {
+        let exists = match entry {
+            BookEntry::Tanka(slug) => tankas.iter().any(|t| t.slug == *slug),
+            BookEntry::Fragment(name) => fragment(name).is_some(),
+        };
+        if exists && !order.contains(entry) {
+            order.push(entry.clone());
+        }
+    }
+    for tanka in tankas {
+        let entry = BookEntry::Tanka(tanka.slug);
+        if !order.contains(&entry) {
+            order.push(entry);
+        }
+    }
+
+    order
+}
+
+/// The entries before and after `entry`, across chapter boundaries
+pub fn neighbours(order: &[BookEntry], entry: &BookEntry) -> (Option<BookEntry>, Option<BookEntry>) {
+    let Some(i) = order.iter().position(|e| e == entry) else {
+        return (None, None);
+    };
+    (i.checked_sub(1).map(|i| order[i].clone()), order.get(i + 1).cloned())
+}
+
+/// The first tanka after `entry`, past any fragments: where a listening
+/// session turns to, since fragments have nothing to play
+pub fn next_tanka(order: &[BookEntry], entry: &BookEntry) -> Option<String> {
+    let i = order.iter().position(|e| e == entry)?;
+    order[i + 1..].iter().find_map(|e| match e {
+        BookEntry::Tanka(slug) => Some(slug.clone()),
+        BookEntry::Fragment(_) => None,
+    })
+}
@@ -0,0 +1,175 @@
+//! What `book.yml` holds, parsed. build.rs reads the file with these same
+//! types to find the fragments to compile in, so nothing here reaches into
+//! the rest of the crate.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Book {
+    pub chapters: Vec<Chapter>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Chapter {
+    pub title: String,
+    #[serde(default)]
+    pub intro: Option<String>,
+    /// Read before the chapter's sections
+    #[serde(default)]
+    pub entries: Vec<BookEntry>,
+    #[serde(default)]
+    pub sections: Vec<Section>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Section {
+    pub title: String,
+    #[serde(default)]
+    pub intro: Option<String>,
+    pub entries: Vec<BookEntry>,
+}
+
+/// One stop in the reading order
+///
+/// ```yaml
+/// entries:
+///   - mom                  # a tanka, by slug
+///   - fragment: "10"       # content/10.fragmentedpoem, shown as it is
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BookEntry {
+    Tanka(String),
+    Fragment(String),
+}
+
+impl BookEntry {
+    pub fn href(&self) -> String {
+        match self {
+            BookEntry::Tanka(slug) => format!("/tanka/{}", slug),
+            BookEntry::Fragment(name) => format!("/fragment/{}", name),
+        }
+    }
+}
+
+/// Whether `name` can only mean `content/<name>.fragmentedpoem`: a plain file
+/// stem, with no path separators, no `..` and nothing hidden
+pub fn is_fragment_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('.') && !name.contains("..") && !name.contains(['/', '\\'])
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FragmentRef {
+    fragment: String,
+}
+
+impl Serialize for BookEntry {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            BookEntry::Tanka(slug) => serializer.serialize_str(slug),
+            BookEntry::Fragment(name) => FragmentRef { fragment: name.clone() }.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BookEntry {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::value::MapAccessDeserializer;
+        use serde::de::{self, MapAccess, Visitor};
+
+        struct EntryVisitor;
+
+        impl<'de> Visitor<'de> for EntryVisitor {
+            type Value = BookEntry;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a tanka slug, or { fragment: <name> }")
+            }
+
+            fn visit_str<E: de::Error>(self, slug: &str) -> Result<Self::Value, E> {
+                Ok(BookEntry::Tanka(slug.to_string()))
+            }
+
+            // Slugs like `null2` are fine, but a bare number reads as one
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_map<M: MapAccess<'de>>(self, map: M) -> Result<Self::Value, M::Error> {
+                let fragment = FragmentRef::deserialize(MapAccessDeserializer::new(map))?;
+                if !is_fragment_name(&fragment.fragment) {
+                    return Err(de::Error::invalid_value(
+                        de::Unexpected::Str(&fragment.fragment),
+                        &"a file name in content/, without / or ..",
+                    ));
+                }
+                Ok(BookEntry::Fragment(fragment.fragment))
+            }
+        }
+
+        deserializer.deserialize_any(EntryVisitor)
+    }
+}
+
+impl Book {
+    pub fn parse(yaml: &str) -> Result<Book, String> {
+        serde_yaml::from_str(yaml).map_err(|e| e.to_string())
+    }
+
+    /// Every entry, chapter by chapter, as listed
+    pub fn entries(&self) -> Vec<&BookEntry> {
+        self.chapters
+            .iter()
+            .flat_map(|chapter| {
+                chapter
+                    .entries
+                    .iter()
+                    .chain(chapter.sections.iter().flat_map(|section| &section.entries))
+            })
+            .collect()
+    }
+
+    /// Fragment names the book reads
+    pub fn fragments(&self) -> Vec<&str> {
+        self.entries()
+            .into_iter()
+            .filter_map(|entry| match entry {
+                BookEntry::Fragment(name) => Some(name.as_str()),
+                BookEntry::Tanka(_) => None,
+            })
+            .collect()
+    }
+
+    /// What's wrong with the book against the content that exists: entries
+    /// that don't, entries listed twice, and tankas left out
+    pub fn problems(&self, slugs: &[String], fragments: &[String]) -> Vec<String> {
+        let mut problems = Vec::new();
+        let entries = self.entries();
+
+        for (i, entry) in entries.iter().enumerate() {
+            match entry {
+                BookEntry::Tanka(slug) if !slugs.contains(slug) => {
+                    problems.push(format!("{}: no such tanka", slug));
+                }
+                BookEntry::Fragment(name) if !fragments.contains(name) => {
+                    problems.push(format!("fragment {}: no content/{}.fragmentedpoem", name, name));
+                }
+                _ => {}
+            }
+            if entries[..i].contains(entry) {
+                problems.push(format!("{} is listed more than once", entry.href()));
+            }
+        }
+
+        for slug in slugs {
+            if !entries.contains(&&BookEntry::Tanka(slug.clone())) {
+                problems.push(format!("{}: not in any chapter", slug));
+            }
+        }
+
+        problems
+    }
+}
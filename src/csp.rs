@@ -0,0 +1,204 @@
+//! Content-Security-Policy derived from the hosts the book actually uses
+//!
+//! The server builds its policy from these allowlists, and the validator
+//! uses the same rules to report content the policy would block.
+
+use std::collections::BTreeSet;
+
+use crate::embed::{BANDCAMP_ORIGIN, SOUNDCLOUD_ORIGIN, YOUTUBE_ORIGIN};
+use crate::model::Tanka;
+
+/// Third-party origins referenced from content, split by how they're loaded
+#[derive(Debug, Default, Clone)]
+pub struct ContentSources {
+    /// Origins loaded in `<iframe>` (bandcamp_embed, soundcloud, youtube)
+    pub frame_origins: BTreeSet<String>,
+    /// Origins loaded in `<img>` (art_link)
+    pub img_origins: BTreeSet<String>,
+    /// Origins loaded in `<audio>` (remote pairing audio; `/audio/` is 'self')
+    pub media_origins: BTreeSet<String>,
+}
+
+/// A URL in content that the policy would refuse to load
+#[derive(Debug, Clone)]
+pub struct Blocked {
+    pub field: &'static str,
+    pub url: String,
+    pub reason: String,
+}
+
+/// Unset fields come through YAML as empty or as the literal string "null"
+fn is_missing(value: &str) -> bool {
+    value.is_empty() || value == "null"
+}
+
+/// Reduce an absolute https URL to its origin, e.g. `https://f4.bcbits.com`
+pub fn https_origin(url: &str) -> Result<String, String> {
+    let rest = match url.split_once("://") {
+        Some(("https", rest)) => rest,
+        Some((scheme, _)) => return Err(format!("{}:// is not allowed, only https://", scheme)),
+        None => return Err("not an absolute URL".to_string()),
+    };
+
+    let authority = rest
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default();
+
+    if authority.is_empty() {
+        return Err("URL has no host".to_string());
+    }
+    if authority.contains('@') {
+        return Err("URL contains credentials".to_string());
+    }
+
+    Ok(format!("https://{}", authority.to_lowercase()))
+}
+
+impl ContentSources {
+    /// Record the origins a tanka loads, returning anything that can't be allowlisted
+    pub fn add(&mut self, tanka: &Tanka) -> Vec<Blocked> {
+        let mut blocked = Vec::new();
+
+        for pairing in tanka.pairings() {
+            if let Some(art_link) = pairing.art_link.as_deref().filter(|a| !is_missing(a)) {
+                match https_origin(art_link) {
+                    Ok(origin) => {
+                        self.img_origins.insert(origin);
+                    }
+                    Err(reason) => blocked.push(Blocked {
+                        field: "art_link",
+                        url: art_link.to_string(),
+                        reason,
+                    }),
+                }
+            }
+
+            // Player URLs are generated, so they always load from the services' own origins
+            if pairing.bandcamp_embed.is_some() {
+                self.frame_origins.insert(BANDCAMP_ORIGIN.to_string());
+            }
+            if pairing.soundcloud.is_some() {
+                self.frame_origins.insert(SOUNDCLOUD_ORIGIN.to_string());
+            }
+            if pairing.youtube.is_some() {
+                self.frame_origins.insert(YOUTUBE_ORIGIN.to_string());
+            }
+
+            if let Some(audio) = pairing.audio.as_deref().filter(|a| !a.starts_with('/')) {
+                match https_origin(audio) {
+                    Ok(origin) => {
+                        self.media_origins.insert(origin);
+                    }
+                    Err(reason) => blocked.push(Blocked {
+                        field: "audio",
+                        url: audio.to_string(),
+                        reason,
+                    }),
+                }
+            }
+        }
+
+        blocked
+    }
+
+    /// Collect origins from every tanka, ignoring anything that would be blocked
+    pub fn from_tankas<'a>(tankas: impl IntoIterator<Item = &'a Tanka>) -> Self {
+        let mut sources = Self::default();
+        for tanka in tankas {
+            sources.add(tanka);
+        }
+        sources
+    }
+
+    /// Build the Content-Security-Policy header value.
+    ///
+    /// `script_hashes` are `'sha256-…'` sources for the inline bootstrap
+    /// script trunk writes into index.html.
+    pub fn content_security_policy(&self, script_hashes: &[String]) -> String {
+        let join = |origins: &BTreeSet<String>| {
+            origins.iter().cloned().collect::<Vec<_>>().join(" ")
+        };
+
+        let mut script_src = vec!["'self'".to_string(), "'wasm-unsafe-eval'".to_string()];
+        script_src.extend(script_hashes.iter().cloned());
+
+        let frame_src = if self.frame_origins.is_empty() {
+            "'none'".to_string()
+        } else {
+            join(&self.frame_origins)
+        };
+
+        let directives = [
+            "default-src 'none'".to_string(),
+            format!("script-src {}", script_src.join(" ")),
+            "style-src 'self'".to_string(),
+            format!("img-src 'self' data: {}", join(&self.img_origins))
+                .trim_end()
+                .to_string(),
+            format!("frame-src {}", frame_src),
+            format!("media-src 'self' {}", join(&self.media_origins))
+                .trim_end()
+                .to_string(),
+            "connect-src 'self'".to_string(),
+            "font-src 'self'".to_string(),
+            "manifest-src 'self'".to_string(),
+            "base-uri 'none'".to_string(),
+            "form-action 'none'".to_string(),
+            "frame-ancestors 'none'".to_string(),
+            "object-src 'none'".to_string(),
+        ];
+
+        directives.join("; ")
+    }
+
+    /// Build the Permissions-Policy header value.
+    ///
+    /// Everything is switched off except autoplay, which embedded players need.
+    pub fn permissions_policy(&self) -> String {
+        let mut autoplay = vec!["self".to_string()];
+        autoplay.extend(self.frame_origins.iter().map(|o| format!("\"{}\"", o)));
+
+        [
+            "camera=()".to_string(),
+            "microphone=()".to_string(),
+            "geolocation=()".to_string(),
+            "payment=()".to_string(),
+            "usb=()".to_string(),
+            format!("autoplay=({})", autoplay.join(" ")),
+        ]
+        .join(", ")
+    }
+}
+
+/// CSP `'sha256-…'` sources for every inline `<script>` in index.html.
+/// Trunk writes its wasm bootstrap inline, so it can't be allowed by `'self'`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn inline_script_hashes(index_html: &str) -> Vec<String> {
+    use base64::Engine;
+    use sha2::{Digest, Sha256};
+
+    let mut hashes = Vec::new();
+    let mut rest = index_html;
+
+    while let Some(start) = rest.find("<script") {
+        rest = &rest[start..];
+        let Some(open_end) = rest.find('>') else { break };
+        let open_tag = &rest[..open_end];
+        rest = &rest[open_end + 1..];
+
+        let Some(close) = rest.find("</script>") else { break };
+        let body = &rest[..close];
+        rest = &rest[close..];
+
+        if open_tag.contains(" src=") {
+            continue;
+        }
+
+        let digest = Sha256::digest(body.as_bytes());
+        let b64 = base64::engine::general_purpose::STANDARD.encode(digest);
+        hashes.push(format!("'sha256-{}'", b64));
+    }
+
+    hashes
+}
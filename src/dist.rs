@@ -0,0 +1,89 @@
+//! trunk's `dist/` as `serve` hands it out: which files can be cached
+//! forever, and their precompressed `.gz` and `.br` siblings
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// File extensions worth precompressing (images are already compressed)
+const COMPRESSIBLE: &[&str] = &[
+    "html", "css", "js", "wasm", "svg", "json", "txt", "xml", "m3u8", "xspf", "jspf",
+];
+
+/// Cache-Control for files whose name changes whenever their content does
+pub const CACHE_IMMUTABLE: &str = "public, max-age=31536000, immutable";
+
+/// Cache-Control for everything else (index.html, healthz, 404s)
+pub const CACHE_REVALIDATE: &str = "no-cache";
+
+/// Whether trunk stamped a content hash into this filename,
+/// e.g. `digital-khole-3f2a1b9c8d7e6f50_bg.wasm` or `style-3f2a1b9c8d7e6f50.css`
+pub fn is_hashed(file_name: &str) -> bool {
+    let stem = file_name.split('.').next().unwrap_or_default();
+    let stem = stem.trim_end_matches("_bg");
+
+    match stem.rsplit_once('-') {
+        Some((_, hash)) => hash.len() >= 16 && hash.chars().all(|c| c.is_ascii_hexdigit()),
+        None => false,
+    }
+}
+
+/// Cache-Control for a response: hashed assets are cached forever, everything
+/// else (and any error) must revalidate
+pub fn cache_control(file_name: &str, success: bool) -> &'static str {
+    if success && is_hashed(file_name) {
+        CACHE_IMMUTABLE
+    } else {
+        CACHE_REVALIDATE
+    }
+}
+
+/// Write `.gz` and `.br` siblings for every compressible file in `dir`,
+/// skipping ones that are already newer than their source
+pub fn precompress(dir: &Path) -> io::Result<usize> {
+    let mut written = 0;
+
+    for entry in fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+
+        if path.is_dir() {
+            written += precompress(&path)?;
+            continue;
+        }
+
+        let ext = path.extension().unwrap_or_default().to_string_lossy();
+        if !COMPRESSIBLE.contains(&ext.as_ref()) {
+            continue;
+        }
+
+        let source_modified = entry.metadata()?.modified()?;
+        let is_fresh = |sibling: &Path| {
+            fs::metadata(sibling)
+                .and_then(|m| m.modified())
+                .is_ok_and(|m| m >= source_modified)
+        };
+
+        let gz_path = PathBuf::from(format!("{}.gz", path.display()));
+        let br_path = PathBuf::from(format!("{}.br", path.display()));
+        if is_fresh(&gz_path) && is_fresh(&br_path) {
+            continue;
+        }
+
+        let bytes = fs::read(&path)?;
+
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        gz.write_all(&bytes)?;
+        fs::write(&gz_path, gz.finish()?)?;
+
+        let mut br = Vec::new();
+        {
+            let mut writer = brotli::CompressorWriter::new(&mut br, 4096, 11, 22);
+            writer.write_all(&bytes)?;
+        }
+        fs::write(&br_path, br)?;
+
+        written += 1;
+    }
+
+    Ok(written)
+}
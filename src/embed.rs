@@ -0,0 +1,498 @@
+//! Embedded players for music pairings
+//!
+//! A pairing can be heard on Bandcamp, SoundCloud, YouTube or from a
+//! self-hosted audio file; [`best_player`] picks the one to embed and
+//! [`pairing_links`] lists the rest.
+//!
+//! `bandcamp_embed` used to be a hand-copied iframe URL. It now deserializes
+//! into a [`BandcampEmbed`] from either that legacy URL or a structured form:
+//!
+//! ```yaml
+//! bandcamp_embed:
+//!   album: 1115961286
+//!   track: 3181857345
+//!   size: large        # small | large
+//!   tracklist: false
+//!   artwork: small     # none | small | big
+//!   # bgcol: "000000"  # pin colors instead of following the site theme
+//!   # linkcol: "4d96ff"
+//! ```
+//!
+//! The iframe URL is generated from it, so player colors follow the site theme
+//! unless pinned. Colors in legacy URLs are ignored for the same reason.
+
+use std::fmt;
+
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::model::MusicPairing;
+
+/// Every generated player URL starts with this
+pub const BANDCAMP_PLAYER: &str = "https://bandcamp.com/EmbeddedPlayer/";
+
+/// Origin the player iframe loads from (for the CSP `frame-src`)
+pub const BANDCAMP_ORIGIN: &str = "https://bandcamp.com";
+
+/// Origin of the SoundCloud widget iframe
+pub const SOUNDCLOUD_ORIGIN: &str = "https://w.soundcloud.com";
+
+/// Origin of the YouTube player iframe (the no-cookie variant)
+pub const YOUTUBE_ORIGIN: &str = "https://www.youtube-nocookie.com";
+
+/// Self-hosted audio lives in `assets/audio/`, served under this prefix
+pub const AUDIO_URL_PREFIX: &str = "/audio/";
+
+/// Extensions `<audio>` can reasonably be expected to play
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "ogg", "opus", "m4a", "flac", "wav"];
+
+/// Background and link colors handed to the player, as 6-digit hex without `#`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerColors {
+    pub background: &'static str,
+    pub link: &'static str,
+}
+
+/// The reader's own palette (`style.css`: black background, blue accents)
+pub const SITE_PLAYER_COLORS: PlayerColors = PlayerColors {
+    background: "000000",
+    link: "4d96ff",
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlayerSize {
+    Small,
+    #[default]
+    Large,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlayerArtwork {
+    None,
+    #[default]
+    Small,
+    Big,
+}
+
+/// A Bandcamp embedded player for an album, a track, or a track within an album
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BandcampEmbed {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub album: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<u64>,
+    pub size: PlayerSize,
+    /// Pinned background color, otherwise the site theme's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bgcol: Option<String>,
+    /// Pinned link color, otherwise the site theme's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub linkcol: Option<String>,
+    pub tracklist: bool,
+    pub artwork: PlayerArtwork,
+}
+
+impl BandcampEmbed {
+    /// Read a legacy `https://bandcamp.com/EmbeddedPlayer/album=.../track=.../` URL
+    pub fn from_url(url: &str) -> Result<Self, String> {
+        let options = url
+            .strip_prefix(BANDCAMP_PLAYER)
+            .or_else(|| url.strip_prefix("http://bandcamp.com/EmbeddedPlayer/"))
+            .ok_or_else(|| format!("not a Bandcamp player URL (expected {}...)", BANDCAMP_PLAYER))?;
+
+        let mut embed = BandcampEmbed {
+            album: None,
+            track: None,
+            size: PlayerSize::default(),
+            bgcol: None,
+            linkcol: None,
+            tracklist: false,
+            artwork: PlayerArtwork::default(),
+        };
+
+        for option in options.split('/').filter(|o| !o.is_empty()) {
+            let (key, value) = option
+                .split_once('=')
+                .ok_or_else(|| format!("malformed option {:?}", option))?;
+
+            let id = || {
+                value
+                    .parse::<u64>()
+                    .map_err(|_| format!("{} id {:?} is not a number", key, value))
+            };
+            let flag = || match value {
+                "true" => Ok(true),
+                "false" => Ok(false),
+                _ => Err(format!("{} must be true or false, got {:?}", key, value)),
+            };
+
+            match key {
+                "album" => embed.album = Some(id()?),
+                "track" => embed.track = Some(id()?),
+                "size" => {
+                    embed.size = match value {
+                        "small" => PlayerSize::Small,
+                        "large" => PlayerSize::Large,
+                        _ => return Err(format!("unknown size {:?}", value)),
+                    }
+                }
+                "artwork" => {
+                    embed.artwork = match value {
+                        "none" => PlayerArtwork::None,
+                        "small" => PlayerArtwork::Small,
+                        "big" => PlayerArtwork::Big,
+                        _ => return Err(format!("unknown artwork {:?}", value)),
+                    }
+                }
+                "tracklist" => embed.tracklist = flag()?,
+                // Colors come from the site theme now; the player is always drawn transparent
+                "bgcol" | "linkcol" => check_color(key, value)?,
+                "transparent" => {
+                    flag()?;
+                }
+                _ => return Err(format!("unknown player option {:?}", key)),
+            }
+        }
+
+        embed.check()?;
+        Ok(embed)
+    }
+
+    /// Reject embeds the player can't show
+    pub fn check(&self) -> Result<(), String> {
+        if self.album.is_none() && self.track.is_none() {
+            return Err("needs an album or track id".to_string());
+        }
+        if let Some(color) = &self.bgcol {
+            check_color("bgcol", color)?;
+        }
+        if let Some(color) = &self.linkcol {
+            check_color("linkcol", color)?;
+        }
+        Ok(())
+    }
+
+    /// The iframe `src`, using pinned colors where set and `theme` otherwise
+    pub fn iframe_url(&self, theme: &PlayerColors) -> String {
+        let mut url = BANDCAMP_PLAYER.to_string();
+
+        if let Some(album) = self.album {
+            url.push_str(&format!("album={}/", album));
+        }
+        if let Some(track) = self.track {
+            url.push_str(&format!("track={}/", track));
+        }
+
+        let size = match self.size {
+            PlayerSize::Small => "small",
+            PlayerSize::Large => "large",
+        };
+        let artwork = match self.artwork {
+            PlayerArtwork::None => "none",
+            PlayerArtwork::Small => "small",
+            PlayerArtwork::Big => "big",
+        };
+
+        url.push_str(&format!(
+            "size={}/bgcol={}/linkcol={}/tracklist={}/artwork={}/transparent=true/",
+            size,
+            self.bgcol.as_deref().unwrap_or(theme.background),
+            self.linkcol.as_deref().unwrap_or(theme.link),
+            self.tracklist,
+            artwork,
+        ));
+
+        url
+    }
+}
+
+fn check_color(key: &str, value: &str) -> Result<(), String> {
+    if value.len() == 6 && value.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(())
+    } else {
+        Err(format!("{} must be 6 hex digits without '#', got {:?}", key, value))
+    }
+}
+
+/// Accepts the legacy URL string or the structured map
+impl<'de> Deserialize<'de> for BandcampEmbed {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EmbedVisitor;
+
+        impl<'de> Visitor<'de> for EmbedVisitor {
+            type Value = BandcampEmbed;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a Bandcamp EmbeddedPlayer URL or a map with album/track ids")
+            }
+
+            fn visit_str<E: de::Error>(self, url: &str) -> Result<Self::Value, E> {
+                BandcampEmbed::from_url(url).map_err(E::custom)
+            }
+
+            fn visit_map<M: MapAccess<'de>>(self, map: M) -> Result<Self::Value, M::Error> {
+                let embed = EmbedFields::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                let embed = BandcampEmbed::from(embed);
+                embed.check().map_err(de::Error::custom)?;
+                Ok(embed)
+            }
+        }
+
+        deserializer.deserialize_any(EmbedVisitor)
+    }
+}
+
+/// The structured YAML form, before [`BandcampEmbed::check`]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EmbedFields {
+    #[serde(default)]
+    album: Option<u64>,
+    #[serde(default)]
+    track: Option<u64>,
+    #[serde(default)]
+    size: PlayerSize,
+    #[serde(default)]
+    bgcol: Option<String>,
+    #[serde(default)]
+    linkcol: Option<String>,
+    #[serde(default)]
+    tracklist: bool,
+    #[serde(default)]
+    artwork: PlayerArtwork,
+}
+
+impl From<EmbedFields> for BandcampEmbed {
+    fn from(f: EmbedFields) -> Self {
+        BandcampEmbed {
+            album: f.album,
+            track: f.track,
+            size: f.size,
+            bgcol: f.bgcol,
+            linkcol: f.linkcol,
+            tracklist: f.tracklist,
+            artwork: f.artwork,
+        }
+    }
+}
+
+/// The player shown on a tanka page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Player {
+    /// iframe `src` of a Bandcamp player
+    Bandcamp(String),
+    /// iframe `src` of a SoundCloud widget
+    SoundCloud(String),
+    /// iframe `src` of a YouTube player
+    YouTube(String),
+    /// `<audio>` `src`
+    Audio(String),
+}
+
+impl Player {
+    /// The same player set to start on its own, for the listening session.
+    ///
+    /// Bandcamp's player has no autoplay option, so it's left as is.
+    pub fn autoplay(self) -> Player {
+        match self {
+            Player::SoundCloud(src) => Player::SoundCloud(src.replace("auto_play=false", "auto_play=true")),
+            Player::YouTube(src) => Player::YouTube(format!("{}?autoplay=1&enablejsapi=1", src)),
+            player => player,
+        }
+    }
+
+    /// Whether the session can tell when this player's track ends
+    pub fn reports_end(&self) -> bool {
+        !matches!(self, Player::Bandcamp(_))
+    }
+
+    /// `(origin, message)` to post into the iframe so it reports when the track ends
+    pub fn end_subscription(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            Player::SoundCloud(_) => Some((SOUNDCLOUD_ORIGIN, r#"{"method":"addEventListener","value":"finish"}"#)),
+            Player::YouTube(_) => Some((YOUTUBE_ORIGIN, r#"{"event":"listening","id":"khole","channel":"widget"}"#)),
+            _ => None,
+        }
+    }
+}
+
+/// What an embedded player said over `postMessage`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerMessage {
+    /// Ready for [`Player::end_subscription`] (SoundCloud drops it before this)
+    Ready,
+    /// The track ended
+    Finished,
+}
+
+/// Read a `message` event from a SoundCloud or YouTube iframe; anything else is `None`
+pub fn read_player_message(origin: &str, data: &str) -> Option<PlayerMessage> {
+    let data: String = data.chars().filter(|c| !c.is_whitespace()).collect();
+
+    if origin == SOUNDCLOUD_ORIGIN {
+        if data.contains(r#""method":"ready""#) {
+            return Some(PlayerMessage::Ready);
+        }
+        if data.contains(r#""method":"finish""#) {
+            return Some(PlayerMessage::Finished);
+        }
+    } else if origin == YOUTUBE_ORIGIN {
+        if data.contains(r#""event":"onReady""#) || data.contains(r#""event":"initialDelivery""#) {
+            return Some(PlayerMessage::Ready);
+        }
+        // 0 is YT.PlayerState.ENDED
+        let ended = data.contains(r#""event":"onStateChange","info":0"#)
+            || data.contains(r#""playerState":0,"#)
+            || data.contains(r#""playerState":0}"#);
+        if ended {
+            return Some(PlayerMessage::Finished);
+        }
+    }
+
+    None
+}
+
+/// A place to listen, for the link list under the player
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairingLink {
+    pub label: &'static str,
+    pub url: String,
+}
+
+/// Pick the best embed available: Bandcamp, then SoundCloud, YouTube, and a self-hosted file.
+///
+/// Takes a pairing from [`Tanka::pairings`](crate::model::Tanka::pairings), which
+/// carries the tanka-level Bandcamp player when it applies.
+pub fn best_player(pairing: &MusicPairing, theme: &PlayerColors) -> Option<Player> {
+    let bandcamp = pairing
+        .bandcamp_embed
+        .as_ref()
+        .filter(|_| !pairing.bandcamp_embed_isprivate)
+        .map(|embed| Player::Bandcamp(embed.iframe_url(theme)));
+
+    bandcamp
+        .or_else(|| {
+            let url = pairing.soundcloud.as_deref()?;
+            soundcloud_player_url(url, theme).ok().map(Player::SoundCloud)
+        })
+        .or_else(|| {
+            let id = youtube_video_id(pairing.youtube.as_deref()?).ok()?;
+            Some(Player::YouTube(format!("{}/embed/{}", YOUTUBE_ORIGIN, id)))
+        })
+        .or_else(|| {
+            let url = pairing.audio.as_deref()?;
+            check_audio(url).ok().map(|_| Player::Audio(url.to_string()))
+        })
+}
+
+/// Every service the pairing links to, in the same order [`best_player`] prefers them
+pub fn pairing_links(pairing: &MusicPairing) -> Vec<PairingLink> {
+    // qr_link usually is the Bandcamp page, so it stands in when none is given
+    let bandcamp = pairing.bandcamp.clone().or_else(|| {
+        pairing
+            .qr_link
+            .clone()
+            .filter(|link| link.contains("bandcamp.com/"))
+    });
+
+    [
+        ("bandcamp", bandcamp),
+        ("soundcloud", pairing.soundcloud.clone()),
+        ("youtube", pairing.youtube.clone()),
+        ("audio file", pairing.audio.clone()),
+    ]
+    .into_iter()
+    .filter_map(|(label, url)| url.map(|url| PairingLink { label, url }))
+    .collect()
+}
+
+/// Problems with the pairing's service links, as `(field, message)`
+pub fn link_problems(pairing: &MusicPairing) -> Vec<(&'static str, String)> {
+    let mut problems = Vec::new();
+
+    if let Some(url) = &pairing.bandcamp {
+        if !url.starts_with("https://") || !url.contains("bandcamp.com/") {
+            problems.push(("bandcamp", format!("{:?} is not an https Bandcamp page", url)));
+        }
+    }
+    if let Some(Err(e)) = pairing.soundcloud.as_deref().map(|u| soundcloud_player_url(u, &SITE_PLAYER_COLORS)) {
+        problems.push(("soundcloud", e));
+    }
+    if let Some(Err(e)) = pairing.youtube.as_deref().map(youtube_video_id) {
+        problems.push(("youtube", e));
+    }
+    if let Some(Err(e)) = pairing.audio.as_deref().map(check_audio) {
+        problems.push(("audio", e));
+    }
+
+    problems
+}
+
+/// SoundCloud widget URL for a `https://soundcloud.com/<artist>/<track>` page
+pub fn soundcloud_player_url(url: &str, theme: &PlayerColors) -> Result<String, String> {
+    let path = url
+        .strip_prefix("https://soundcloud.com/")
+        .filter(|path| !path.is_empty())
+        .ok_or_else(|| format!("{:?} is not a https://soundcloud.com/ page", url))?;
+
+    Ok(format!(
+        "{}/player/?url={}&color=%23{}&auto_play=false&visual=false",
+        SOUNDCLOUD_ORIGIN,
+        percent_encode(&format!("https://soundcloud.com/{}", path)),
+        theme.link,
+    ))
+}
+
+/// Video id from `youtube.com/watch?v=`, `youtu.be/` or `youtube.com/embed/` URLs
+pub fn youtube_video_id(url: &str) -> Result<String, String> {
+    let rest = url
+        .strip_prefix("https://")
+        .ok_or_else(|| format!("{:?} is not an https URL", url))?;
+    let rest = rest.strip_prefix("www.").unwrap_or(rest);
+    let rest = rest.strip_prefix("m.").unwrap_or(rest);
+
+    let id = if let Some(query) = rest.strip_prefix("youtube.com/watch?") {
+        query.split('&').find_map(|pair| pair.strip_prefix("v="))
+    } else if let Some(path) = rest.strip_prefix("youtu.be/") {
+        Some(path)
+    } else {
+        rest.strip_prefix("youtube.com/embed/")
+    };
+
+    let id = id
+        .map(|id| id.split(['?', '&', '#', '/']).next().unwrap_or_default())
+        .unwrap_or_default();
+
+    // Video ids are 11 characters of [A-Za-z0-9_-]
+    if id.len() == 11 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        Ok(id.to_string())
+    } else {
+        Err(format!("no YouTube video id in {:?}", url))
+    }
+}
+
+/// A self-hosted file under `/audio/` (or an https URL) in a format browsers play
+pub fn check_audio(url: &str) -> Result<(), String> {
+    if !url.starts_with(AUDIO_URL_PREFIX) && !url.starts_with("https://") {
+        return Err(format!("{:?} must start with {} or https://", url, AUDIO_URL_PREFIX));
+    }
+
+    let ext = url.rsplit('.').next().unwrap_or_default().to_lowercase();
+    if AUDIO_EXTENSIONS.contains(&ext.as_str()) {
+        Ok(())
+    } else {
+        Err(format!("{:?} is not one of {}", url, AUDIO_EXTENSIONS.join("/")))
+    }
+}
+
+/// Encode everything but RFC 3986 unreserved characters
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
@@ -0,0 +1,207 @@
+//! Perlin noise favicon, regenerated on every load
+//!
+//! Inspired by Well of Souls "pi" grain of sand circle estimation. Browser only
+//! (it draws its randomness from `Math.random`).
+
+use image::ImageEncoder;
+
+
+/// Permutation table for Perlin noise (randomized on each call)
+fn generate_perm_table() -> [u8; 512] {
+    let mut perm: [u8; 256] = core::array::from_fn(|i| i as u8);
+
+    // Fisher-Yates shuffle using js_sys::Math::random()
+    for i in (1..256).rev() {
+        let j = (js_sys::Math::random() * (i + 1) as f64) as usize;
+        perm.swap(i, j);
+    }
+
+    // Double the permutation table
+    let mut result = [0u8; 512];
+    for i in 0..512 {
+        result[i] = perm[i % 256];
+    }
+    result
+}
+
+/// Fade function for smooth interpolation: 6t^5 - 15t^4 + 10t^3
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+/// Linear interpolation
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + t * (b - a)
+}
+
+/// Gradient function - returns dot product of gradient and distance vector
+fn grad(hash: u8, x: f64, y: f64) -> f64 {
+    match hash & 3 {
+        0 => x + y,
+        1 => -x + y,
+        2 => x - y,
+        _ => -x - y,
+    }
+}
+
+/// 2D Perlin noise
+fn perlin_noise(x: f64, y: f64, perm: &[u8; 512]) -> f64 {
+    // Find unit grid cell
+    let xi = (x.floor() as i32 & 255) as usize;
+    let yi = (y.floor() as i32 & 255) as usize;
+
+    // Relative position within cell
+    let xf = x - x.floor();
+    let yf = y - y.floor();
+
+    // Fade curves
+    let u = fade(xf);
+    let v = fade(yf);
+
+    // Hash coordinates of the 4 corners
+    let aa = perm[perm[xi] as usize + yi];
+    let ab = perm[perm[xi] as usize + yi + 1];
+    let ba = perm[perm[xi + 1] as usize + yi];
+    let bb = perm[perm[xi + 1] as usize + yi + 1];
+
+    // Blend
+    let x1 = lerp(grad(aa, xf, yf), grad(ba, xf - 1.0, yf), u);
+    let x2 = lerp(grad(ab, xf, yf - 1.0), grad(bb, xf - 1.0, yf - 1.0), u);
+
+    lerp(x1, x2, v)
+}
+
+/// Octave noise (fractal Brownian motion) for more interesting texture
+fn octave_noise(x: f64, y: f64, octaves: u32, persistence: f64, perm: &[u8; 512]) -> f64 {
+    let mut total = 0.0;
+    let mut frequency = 1.0;
+    let mut amplitude = 1.0;
+    let mut max_value = 0.0;
+
+    for _ in 0..octaves {
+        total += perlin_noise(x * frequency, y * frequency, perm) * amplitude;
+        max_value += amplitude;
+        amplitude *= persistence;
+        frequency *= 2.0;
+    }
+
+    total / max_value
+}
+
+/// Generate favicon as a Perlin noise "hole" - darker toward center, tinted by the theme
+fn generate_favicon_data_uri(tint: [u8; 3]) -> String {
+    use base64::Engine;
+
+    const SIZE: u32 = 32;
+    let perm = generate_perm_table();
+
+    // Random offset for each favicon generation (makes each load unique)
+    let offset_x = js_sys::Math::random() * 1000.0;
+    let offset_y = js_sys::Math::random() * 1000.0;
+
+    // Noise scale - higher = more zoomed out noise
+    let noise_scale = 4.0 + js_sys::Math::random() * 2.0;
+
+    let mut pixels = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+    let center = SIZE as f64 / 2.0;
+    let max_dist = center; // Maximum distance from center to edge
+
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let px = x as f64;
+            let py = y as f64;
+
+            // Distance from center, normalized to 0-1
+            let dx = px - center;
+            let dy = py - center;
+            let dist = (dx * dx + dy * dy).sqrt() / max_dist;
+
+            // Get Perlin noise value (-1 to 1), remap to 0-1
+            let noise_x = (px / SIZE as f64) * noise_scale + offset_x;
+            let noise_y = (py / SIZE as f64) * noise_scale + offset_y;
+            let noise = (octave_noise(noise_x, noise_y, 3, 0.5, &perm) + 1.0) / 2.0;
+
+            // "Hole" effect: darker toward center
+            // Invert distance so center = 0 (darkest), edge = 1 (lightest)
+            // Apply a curve to make the hole more pronounced
+            let radial = dist.powf(0.7); // <1 = sharper edge, >1 = softer edge
+
+            // Combine: base darkness from radial, modulated by noise
+            // More noise influence near the edges, less in the dark center
+            let noise_influence = 0.3 + radial * 0.5;
+            let brightness = radial * (1.0 - noise_influence + noise * noise_influence);
+
+            // Add some "grain" - random speckle like the sand dropping effect
+            let grain = if js_sys::Math::random() < 0.1 {
+                (js_sys::Math::random() - 0.5) * 0.15
+            } else {
+                0.0
+            };
+
+            let final_brightness = (brightness + grain).clamp(0.0, 1.0);
+            let pixel_value = (final_brightness * 255.0) as u8;
+
+            // RGBA - the tint scaled by brightness, with full opacity
+            for channel in tint {
+                pixels.push((u16::from(channel) * u16::from(pixel_value) / 255) as u8);
+            }
+            pixels.push(255);
+        }
+    }
+
+    // Encode as PNG
+    let mut png_bytes: Vec<u8> = Vec::new();
+    let encoder = image::codecs::png::PngEncoder::new(&mut png_bytes);
+    if encoder.write_image(
+        &pixels,
+        SIZE,
+        SIZE,
+        image::ExtendedColorType::Rgba8,
+    ).is_err() {
+        return String::new();
+    }
+
+    let b64 = base64::engine::general_purpose::STANDARD.encode(&png_bytes);
+    format!("data:image/png;base64,{}", b64)
+}
+
+/// Set the favicon dynamically via DOM manipulation
+pub fn set_favicon(tint: [u8; 3]) {
+    let window = match web_sys::window() {
+        Some(w) => w,
+        None => return,
+    };
+    let document = match window.document() {
+        Some(d) => d,
+        None => return,
+    };
+
+    // Generate the favicon
+    let favicon_uri = generate_favicon_data_uri(tint);
+    if favicon_uri.is_empty() {
+        return;
+    }
+
+    // Create or update the link element
+    let head = match document.head() {
+        Some(h) => h,
+        None => return,
+    };
+
+    // Remove existing favicon if present
+    if let Ok(Some(existing)) = document.query_selector("link[rel='icon']") {
+        existing.remove();
+    }
+
+    // Create new favicon link
+    let link = match document.create_element("link") {
+        Ok(el) => el,
+        Err(_) => return,
+    };
+
+    let _ = link.set_attribute("rel", "icon");
+    let _ = link.set_attribute("type", "image/png");
+    let _ = link.set_attribute("href", &favicon_uri);
+
+    let _ = head.append_child(&link);
+}
@@ -0,0 +1,80 @@
+//! The book as an RSS feed, led by the day's pick (native only)
+//!
+//! `serve` renders it per request at `/rss.xml`, so the first item changes
+//! with the date like `/today` does.
+
+use crate::model::TankaEntry;
+use crate::pick::{civil, daily, iso_date};
+use crate::soundtrack::escape_xml;
+
+/// Where `serve` answers with the feed
+pub const RSS_PATH: &str = "/rss.xml";
+
+const FEED_TITLE: &str = "Digital K-Hole";
+const FEED_DESCRIPTION: &str = "A poetry book about growing up on the internet, told through tanka and music pairings.";
+
+/// RSS 2.0 for every tanka in book order, after an item for the day's pick.
+/// `base_url` (e.g. `https://example.com`) makes the links absolute.
+pub fn rss(tankas: &[TankaEntry], base_url: &str, days: i64) -> String {
+    let base_url = base_url.trim_end_matches('/');
+    let link = |entry: &TankaEntry| format!("{}/tanka/{}", base_url, entry.slug);
+
+    let mut rss = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">\n<channel>\n");
+    rss.push_str(&format!("  <title>{}</title>\n", escape_xml(FEED_TITLE)));
+    rss.push_str(&format!("  <link>{}/</link>\n", escape_xml(base_url)));
+    rss.push_str(&format!("  <description>{}</description>\n", escape_xml(FEED_DESCRIPTION)));
+    rss.push_str(&format!("  <lastBuildDate>{}</lastBuildDate>\n", rfc822(days)));
+
+    if let Some(entry) = daily(tankas, days) {
+        rss.push_str("  <item>\n");
+        rss.push_str(&format!("    <title>today: {}</title>\n", escape_xml(&entry.title())));
+        rss.push_str(&format!("    <link>{}</link>\n", escape_xml(&link(entry))));
+        rss.push_str(&format!("    <description>{}</description>\n", escape_xml(&summary(entry))));
+        rss.push_str(&format!(
+            "    <guid isPermaLink=\"false\">{}/today/{}</guid>\n",
+            escape_xml(base_url),
+            iso_date(days)
+        ));
+        rss.push_str(&format!("    <pubDate>{}</pubDate>\n", rfc822(days)));
+        rss.push_str("  </item>\n");
+    }
+
+    for entry in tankas {
+        rss.push_str("  <item>\n");
+        rss.push_str(&format!("    <title>{}</title>\n", escape_xml(&entry.title())));
+        rss.push_str(&format!("    <link>{}</link>\n", escape_xml(&link(entry))));
+        rss.push_str(&format!("    <description>{}</description>\n", escape_xml(&summary(entry))));
+        rss.push_str(&format!("    <guid>{}</guid>\n", escape_xml(&link(entry))));
+        rss.push_str("  </item>\n");
+    }
+
+    rss.push_str("</channel>\n</rss>\n");
+    rss
+}
+
+/// The verses on one line, as in the page's meta description
+fn summary(entry: &TankaEntry) -> String {
+    entry
+        .tanka
+        .tanka
+        .as_vec()
+        .into_iter()
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+/// Midnight UTC of a day, e.g. `Sun, 18 Oct 2026 00:00:00 GMT`
+fn rfc822(days: i64) -> String {
+    const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+    let (year, month, day) = civil(days);
+    format!(
+        "{}, {:02} {} {:04} 00:00:00 GMT",
+        WEEKDAYS[days.rem_euclid(7) as usize],
+        day,
+        MONTHS[month as usize - 1],
+        year
+    )
+}
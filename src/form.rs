@@ -0,0 +1,94 @@
+//! Poem forms: tanka, and the other short forms a file can hold instead
+//!
+//! `form:` picks one (files without it are tankas). The form decides how many
+//! lines there are, the syllable pattern `just validate` checks Japanese
+//! against, and how the page lays the lines out.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PoemForm {
+    /// 5-7-5-7-7
+    #[default]
+    Tanka,
+    /// 5-7-5
+    Haiku,
+    /// Two 5-7-7 katauta, read as a question and its answer
+    Sedoka,
+    /// Adelaide Crapsey's 2-4-6-8-2
+    Cinquain,
+    /// Any number of lines; blank ones split stanzas
+    FreeVerse,
+}
+
+impl PoemForm {
+    pub const ALL: [PoemForm; 5] = [
+        PoemForm::Tanka,
+        PoemForm::Haiku,
+        PoemForm::Sedoka,
+        PoemForm::Cinquain,
+        PoemForm::FreeVerse,
+    ];
+
+    /// Value of `form:`, and the page's `form-<name>` class
+    pub fn name(self) -> &'static str {
+        match self {
+            PoemForm::Tanka => "tanka",
+            PoemForm::Haiku => "haiku",
+            PoemForm::Sedoka => "sedoka",
+            PoemForm::Cinquain => "cinquain",
+            PoemForm::FreeVerse => "free-verse",
+        }
+    }
+
+    /// How `top_flavor` names it
+    pub fn label(self) -> &'static str {
+        match self {
+            PoemForm::FreeVerse => "free verse",
+            form => form.name(),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<PoemForm> {
+        PoemForm::ALL.into_iter().find(|f| f.name() == name)
+    }
+
+    /// Syllables (morae, in Japanese) per line; `None` for free verse
+    pub fn syllables(self) -> Option<&'static [usize]> {
+        match self {
+            PoemForm::Tanka => Some(&[5, 7, 5, 7, 7]),
+            PoemForm::Haiku => Some(&[5, 7, 5]),
+            PoemForm::Sedoka => Some(&[5, 7, 7, 5, 7, 7]),
+            PoemForm::Cinquain => Some(&[2, 4, 6, 8, 2]),
+            PoemForm::FreeVerse => None,
+        }
+    }
+
+    pub fn line_count(self) -> Option<usize> {
+        self.syllables().map(<[usize]>::len)
+    }
+
+    /// The syllables as `top_flavor` writes them, e.g. `575` for a haiku
+    pub fn pattern(self) -> Option<String> {
+        self.syllables().map(|counts| counts.iter().map(usize::to_string).collect())
+    }
+
+    /// Lines per stanza on the page, for the fixed forms
+    pub fn stanzas(self) -> &'static [usize] {
+        match self {
+            PoemForm::Tanka | PoemForm::Cinquain => &[5],
+            PoemForm::Haiku => &[3],
+            PoemForm::Sedoka => &[3, 3],
+            PoemForm::FreeVerse => &[],
+        }
+    }
+}
+
+impl fmt::Display for PoemForm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
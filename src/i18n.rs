@@ -0,0 +1,175 @@
+//! UI strings in every language the site speaks
+//!
+//! Messages are Fluent files under `locales/`, compiled in. A message missing
+//! from a locale falls back to English, and then to its id; `just validate`
+//! and the tests catch both before they ship.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use fluent_langneg::{negotiate_languages, NegotiationStrategy};
+use unic_langid::LanguageIdentifier;
+
+/// Every message id the UI asks for, `theme-<name>` ids included. Each locale
+/// has to carry all of them; `src/app.rs` only asks for ids listed here.
+pub const MESSAGES: &[&str] = &[
+    // Age gate and the printed warning
+    "age-gate-warning", "age-gate-title", "age-gate-about", "age-gate-audience", "age-gate-exit",
+    "age-gate-enter", "print-warning-title", "print-warning-about",
+    // Index
+    "site-description", "shell-open", "shell-close", "shell-hint", "shell-input-label",
+    "soundtrack-prompt", "print-all", "toc-unsorted", "tags-link", "random-link", "random-picking",
+    // Content warnings
+    "cw-label", "cw-hide", "cw-show", "cw-always-show", "cw-always-hide", "cw-hide-some",
+    // Settings panels
+    "theme-system", "theme-dark", "theme-light", "theme-high-contrast", "theme-templeos",
+    "locale-browser",
+    // Listening session
+    "session-about", "session-pause", "session-start", "session-stop-long", "session-stop",
+    "session-on", "session-up-next", "session-nothing", "session-bandcamp", "session-next",
+    // Reader data
+    "fav-add", "fav-remove", "read-mark", "read-unmark", "resume", "reader-about", "reader-counts",
+    "reader-export", "reader-import", "reader-import-label", "reader-imported",
+    "reader-import-failed",
+    // Music pairing
+    "art-alt", "qr-alt", "pairing-by", "pairing-from", "pairing-at", "volume-hint", "volume-meter",
+    "links-also", "links-listen", "private-notice",
+    // Tanka page
+    "ja-vertical", "ja-horizontal", "see-also", "verse-link", "share-copy", "share-copied",
+    "share-failed", "nav-prev", "nav-next", "nav-print", "not-found", "back-to-index",
+    "fragment-not-found", "page-not-found",
+    // Tags
+    "tags-title", "tag-not-found", "tag-empty",
+    // Print views
+    "print-title", "print-all-title", "print-back", "print-button",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    Ja,
+}
+
+impl Locale {
+    /// Every locale, in the order the switcher lists them
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Ja];
+
+    /// BCP 47 code: `lang` on `<html>`, the stored preference and the
+    /// `<name>.tanka.<code>.yml` suffix
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Ja => "ja",
+        }
+    }
+
+    /// Name shown in the switcher, in the language itself
+    pub fn label(self) -> &'static str {
+        match self {
+            Locale::En => "english",
+            Locale::Ja => "日本語",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Locale> {
+        Locale::ALL.into_iter().find(|l| l.code() == code)
+    }
+
+    /// The Fluent source for this locale
+    pub fn source(self) -> &'static str {
+        match self {
+            Locale::En => include_str!("../locales/en.ftl"),
+            Locale::Ja => include_str!("../locales/ja.ftl"),
+        }
+    }
+
+    /// Best match for the reader's languages (`navigator.languages`, most
+    /// preferred first), falling back to English
+    pub fn negotiate<S: AsRef<str>>(requested: &[S]) -> Locale {
+        let requested: Vec<LanguageIdentifier> =
+            requested.iter().filter_map(|r| r.as_ref().parse().ok()).collect();
+        let available: Vec<LanguageIdentifier> = Locale::ALL.iter().map(|l| langid(*l)).collect();
+        let default = langid(Locale::default());
+
+        negotiate_languages(&requested, &available, Some(&default), NegotiationStrategy::Lookup)
+            .first()
+            .and_then(|id| Locale::from_code(id.language.as_str()))
+            .unwrap_or_default()
+    }
+
+    /// The message `id`
+    pub fn tr(self, id: &str) -> String {
+        self.tr_with(id, &[])
+    }
+
+    /// The message `id` with its `{ $variables }` filled in
+    pub fn tr_with(self, id: &str, args: &[(&str, FluentValue)]) -> String {
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+        let args = (!args.is_empty()).then_some(&fluent_args);
+
+        [self, Locale::default()]
+            .into_iter()
+            .find_map(|locale| format(bundle(locale), id, args))
+            .unwrap_or_else(|| id.to_string())
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// Ids of the messages in a Fluent source, in file order
+pub fn message_ids(source: &str) -> Result<Vec<String>, String> {
+    let resource = fluent_syntax::parser::parse(source)
+        .map_err(|(_, errors)| errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("; "))?;
+
+    Ok(resource
+        .body
+        .iter()
+        .filter_map(|entry| match entry {
+            fluent_syntax::ast::Entry::Message(message) => Some(message.id.name.to_string()),
+            _ => None,
+        })
+        .collect())
+}
+
+fn langid(locale: Locale) -> LanguageIdentifier {
+    locale.code().parse().unwrap_or_default()
+}
+
+/// Parsed once per locale. Entries that fail to parse are dropped (the
+/// validator reports them) and fall back like missing messages.
+fn bundle(locale: Locale) -> &'static FluentBundle<FluentResource> {
+    static BUNDLES: OnceLock<Vec<FluentBundle<FluentResource>>> = OnceLock::new();
+
+    let bundles = BUNDLES.get_or_init(|| {
+        Locale::ALL
+            .into_iter()
+            .map(|locale| {
+                let resource = FluentResource::try_new(locale.source().to_string())
+                    .unwrap_or_else(|(resource, _)| resource);
+                let mut bundle = FluentBundle::new_concurrent(vec![langid(locale)]);
+                // No bidi isolation marks: the text goes straight into the DOM
+                bundle.set_use_isolating(false);
+                let _ = bundle.add_resource(resource);
+                bundle
+            })
+            .collect()
+    });
+    &bundles[Locale::ALL.iter().position(|l| *l == locale).unwrap_or(0)]
+}
+
+fn format(bundle: &FluentBundle<FluentResource>, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, args, &mut errors);
+    errors.is_empty().then(|| text.into_owned())
+}
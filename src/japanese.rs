@@ -0,0 +1,95 @@
+//! Japanese verses: ruby (furigana) markup and mora counting
+//!
+//! Readings are written inline as `{base|reading}`, e.g. `{物|もの}の{哀|あわ}れ`,
+//! and render as `<ruby>`. The validator counts morae (on-ji) from the kana,
+//! using the reading wherever one is given, against the poem's form.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RubySegment {
+    Plain(String),
+    Ruby { base: String, reading: String },
+}
+
+/// Split `{base|reading}` markup into plain text and annotated runs
+pub fn parse_ruby(text: &str) -> Result<Vec<RubySegment>, String> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+
+    while let Some(open) = rest.find('{') {
+        if open > 0 {
+            segments.push(RubySegment::Plain(rest[..open].to_string()));
+        }
+        let close = rest[open..]
+            .find('}')
+            .map(|i| open + i)
+            .ok_or_else(|| format!("unclosed {{ in {:?}", text))?;
+        let inner = &rest[open + 1..close];
+        let (base, reading) = inner
+            .split_once('|')
+            .ok_or_else(|| format!("{{{}}} needs a reading, like {{漢字|かんじ}}", inner))?;
+        if base.is_empty() || reading.is_empty() || reading.contains('{') {
+            return Err(format!("malformed ruby {{{}}} in {:?}", inner, text));
+        }
+        segments.push(RubySegment::Ruby {
+            base: base.to_string(),
+            reading: reading.to_string(),
+        });
+        rest = &rest[close + 1..];
+    }
+
+    if rest.contains('}') {
+        return Err(format!("stray }} in {:?}", text));
+    }
+    if !rest.is_empty() {
+        segments.push(RubySegment::Plain(rest.to_string()));
+    }
+    Ok(segments)
+}
+
+/// The verse without its readings
+pub fn plain_text(segments: &[RubySegment]) -> String {
+    segments
+        .iter()
+        .map(|s| match s {
+            RubySegment::Plain(text) => text.as_str(),
+            RubySegment::Ruby { base, .. } => base.as_str(),
+        })
+        .collect()
+}
+
+/// Morae in a verse. Fails on the first character that can't be counted
+/// (kanji without a reading, latin letters).
+pub fn count_morae(segments: &[RubySegment]) -> Result<usize, String> {
+    let mut count = 0;
+    for segment in segments {
+        let kana = match segment {
+            RubySegment::Plain(text) => text,
+            RubySegment::Ruby { reading, .. } => reading,
+        };
+        for c in kana.chars() {
+            if is_small_kana(c) || is_ignored(c) {
+                continue;
+            }
+            if is_kana(c) {
+                count += 1;
+            } else {
+                return Err(format!("can't count {:?} (give kanji a reading like {{{}|...}})", c, c));
+            }
+        }
+    }
+    Ok(count)
+}
+
+/// Hiragana, katakana and the long vowel mark (っ, ん and ー each count)
+fn is_kana(c: char) -> bool {
+    matches!(c, '\u{3041}'..='\u{3096}' | '\u{30A1}'..='\u{30FA}' | 'ー')
+}
+
+/// Small kana that merge with the one before (きゃ is one mora); small っ isn't one of them
+fn is_small_kana(c: char) -> bool {
+    "ぁぃぅぇぉゃゅょゎゕゖァィゥェォャュョヮヵヶ".contains(c)
+}
+
+fn is_ignored(c: char) -> bool {
+    c.is_whitespace() || c.is_ascii_punctuation() || "、。！？「」『』・…〜　".contains(c)
+}
@@ -0,0 +1,41 @@
+//! Digital K-Hole shared library
+//!
+//! Everything that both the WASM reader (`src/main.rs`) and the native
+//! binaries (`validate`, `serve`, ...) need to agree on lives here.
+
+// Leptos' nested view types outgrow the default limit when rendering on the server
+#![recursion_limit = "256"]
+
+pub mod app;
+pub mod art;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod art_fetch;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod bandcamp;
+pub mod book;
+pub mod csp;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod dist;
+pub mod embed;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod feed;
+pub mod favicon;
+pub mod form;
+pub mod i18n;
+pub mod japanese;
+pub mod model;
+pub mod pick;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod related;
+pub mod share;
+pub mod shell;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod soundtrack;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod ssr;
+pub mod storage;
+pub mod tags;
+pub mod theme;
+pub mod volume;
+
+pub use model::*;
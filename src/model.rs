@@ -0,0 +1,520 @@
+//! Tanka data model shared by the SPA and the native tools in `src/bin/`
+
+use serde::{Deserialize, Serialize};
+
+use crate::embed::BandcampEmbed;
+use crate::form::PoemForm;
+use crate::volume::VolumeLevel;
+
+/// Who wrote the book, shown as the owner in the `ls` listing
+pub const AUTHOR: &str = "henry";
+
+/// Tanka poem with music pairing metadata (or, with `form:`, another kind of poem)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tanka {
+    #[serde(default)]
+    pub form: PoemForm,
+    pub top_flavor: String,
+    pub qr_link: String,
+    pub art_link: String,
+    #[serde(default)]
+    pub bandcamp_embed: Option<BandcampEmbed>,
+    #[serde(default)]
+    pub bandcamp_embed_isprivate: bool,
+    /// One pairing, or an ordered setlist of them
+    pub recommended_music_pairing: Pairings,
+    /// The lines, under `tanka:` or (for the other forms) `poem:`
+    #[serde(alias = "poem")]
+    pub tanka: Poem,
+    pub tankadesc: String,
+    #[serde(default)]
+    pub tastingnotes: Option<String>,
+    #[serde(default)]
+    pub content_warnings: Vec<String>,
+    /// Themes from `tags.yml`, shown as chips linking to `/tag/<name>`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Someone other than the author wrote this one
+    #[serde(default)]
+    pub guest_contributor: Option<GuestContributor>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuestContributor {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MusicPairing {
+    pub track: String,
+    pub artist: String,
+    pub album: String,
+    /// `None` while the pairing is still a placeholder (`volume_level: null`)
+    #[serde(default)]
+    pub volume_level: Option<VolumeLevel>,
+    /// Bandcamp page, when it isn't `qr_link`
+    #[serde(default)]
+    pub bandcamp: Option<String>,
+    /// `https://soundcloud.com/<artist>/<track>`
+    #[serde(default)]
+    pub soundcloud: Option<String>,
+    /// Any youtube.com / youtu.be video URL
+    #[serde(default)]
+    pub youtube: Option<String>,
+    /// Self-hosted file under `/audio/` (from `assets/audio/`)
+    #[serde(default)]
+    pub audio: Option<String>,
+    /// Where to find it when there's nothing to link, e.g. "only on the CD"
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Shown with the entry in a setlist, e.g. why it comes next
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Per-entry overrides of the tanka-level fields, for setlists.
+    /// The first entry falls back to the tanka's own; see [`Tanka::pairings`].
+    #[serde(default)]
+    pub qr_link: Option<String>,
+    #[serde(default)]
+    pub art_link: Option<String>,
+    #[serde(default)]
+    pub bandcamp_embed: Option<BandcampEmbed>,
+    #[serde(default)]
+    pub bandcamp_embed_isprivate: bool,
+}
+
+/// `recommended_music_pairing`: a single pairing (the original format) or a list
+#[derive(Debug, Clone)]
+pub struct Pairings(Vec<MusicPairing>);
+
+impl Pairings {
+    /// The first (or only) pairing
+    pub fn first(&self) -> &MusicPairing {
+        &self.0[0]
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, MusicPairing> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Always false, deserializing rejects empty lists
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Streams the map or list straight from the YAML deserializer (not via
+/// `#[serde(untagged)]`, which loses the coercion of `null` into strings)
+impl<'de> Deserialize<'de> for Pairings {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
+        use serde::de::{self, MapAccess, SeqAccess, Visitor};
+
+        struct PairingsVisitor;
+
+        impl<'de> Visitor<'de> for PairingsVisitor {
+            type Value = Pairings;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a music pairing or a list of them")
+            }
+
+            fn visit_map<M: MapAccess<'de>>(self, map: M) -> Result<Self::Value, M::Error> {
+                let pairing = MusicPairing::deserialize(MapAccessDeserializer::new(map))?;
+                Ok(Pairings(vec![pairing]))
+            }
+
+            fn visit_seq<S: SeqAccess<'de>>(self, seq: S) -> Result<Self::Value, S::Error> {
+                let list = Vec::<MusicPairing>::deserialize(SeqAccessDeserializer::new(seq))?;
+                if list.is_empty() {
+                    return Err(de::Error::custom("recommended_music_pairing list is empty"));
+                }
+                Ok(Pairings(list))
+            }
+        }
+
+        deserializer.deserialize_any(PairingsVisitor)
+    }
+}
+
+impl Serialize for Pairings {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0.as_slice() {
+            [single] => single.serialize(serializer),
+            list => list.serialize(serializer),
+        }
+    }
+}
+
+impl Tanka {
+    /// Every pairing with the links it should show filled in.
+    ///
+    /// The tanka-level `qr_link`, `art_link` and Bandcamp player belong to
+    /// the first entry only; later entries show what they list themselves.
+    pub fn pairings(&self) -> Vec<MusicPairing> {
+        self.recommended_music_pairing
+            .iter()
+            .enumerate()
+            .map(|(i, pairing)| {
+                let mut pairing = pairing.clone();
+                if i == 0 {
+                    pairing.qr_link.get_or_insert_with(|| self.qr_link.clone());
+                    pairing.art_link.get_or_insert_with(|| self.art_link.clone());
+                }
+                if i == 0 && pairing.bandcamp_embed.is_none() {
+                    pairing.bandcamp_embed = self.bandcamp_embed.clone();
+                    pairing.bandcamp_embed_isprivate |= self.bandcamp_embed_isprivate;
+                }
+                pairing
+            })
+            .collect()
+    }
+}
+
+/// The lines of a poem, `1:` to `n:` (or a plain list, handy for free verse)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Poem {
+    lines: Vec<Verse>,
+}
+
+impl Poem {
+    /// The text of each verse, as the page shows it
+    pub fn as_vec(&self) -> Vec<&str> {
+        self.lines.iter().map(|v| v.text.as_str()).collect()
+    }
+
+    pub fn lines(&self) -> &[Verse] {
+        &self.lines
+    }
+
+    /// The verse a verse link points at: the nth line the page shows, from 1
+    /// (the blank lines between free verse stanzas aren't shown, so don't count)
+    pub fn verse(&self, n: usize) -> Option<&Verse> {
+        self.lines.iter().filter(|v| !v.text.trim().is_empty()).nth(n.checked_sub(1)?)
+    }
+
+    /// Whether any verse carries Japanese
+    pub fn has_japanese(&self) -> bool {
+        self.lines.iter().any(|v| v.ja.is_some())
+    }
+
+    /// The lines grouped as `form` lays them out. Free verse breaks at blank
+    /// lines (which are dropped); a fixed form with the wrong number of lines
+    /// stays in one piece.
+    pub fn stanzas(&self, form: PoemForm) -> Vec<&[Verse]> {
+        if form == PoemForm::FreeVerse {
+            return self
+                .lines
+                .split(|v| v.text.trim().is_empty())
+                .filter(|stanza| !stanza.is_empty())
+                .collect();
+        }
+
+        let sizes = form.stanzas();
+        if sizes.iter().sum::<usize>() != self.lines.len() {
+            return vec![&self.lines];
+        }
+        let mut rest = self.lines.as_slice();
+        sizes
+            .iter()
+            .map(|&size| {
+                let (stanza, tail) = rest.split_at(size);
+                rest = tail;
+                stanza
+            })
+            .collect()
+    }
+
+    fn checked<E: serde::de::Error>(lines: Vec<Verse>) -> Result<Poem, E> {
+        if lines.is_empty() {
+            return Err(E::custom("a poem needs at least one line"));
+        }
+        Ok(Poem { lines })
+    }
+}
+
+impl Serialize for Poem {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(self.lines.len()))?;
+        for (i, verse) in self.lines.iter().enumerate() {
+            map.serialize_entry(&(i + 1).to_string(), verse)?;
+        }
+        map.end()
+    }
+}
+
+/// Numbered lines have to run from 1 with no gaps, in any order
+impl<'de> Deserialize<'de> for Poem {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::value::SeqAccessDeserializer;
+        use serde::de::{self, MapAccess, SeqAccess, Visitor};
+
+        struct LineNumber(usize);
+
+        impl<'de> Deserialize<'de> for LineNumber {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct LineNumberVisitor;
+
+                impl Visitor<'_> for LineNumberVisitor {
+                    type Value = LineNumber;
+
+                    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        f.write_str("a line number")
+                    }
+
+                    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                        Ok(LineNumber(v as usize))
+                    }
+
+                    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                        usize::try_from(v)
+                            .map(LineNumber)
+                            .map_err(|_| E::custom(format!("line {} doesn't exist, lines start at 1", v)))
+                    }
+
+                    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                        v.parse()
+                            .map(LineNumber)
+                            .map_err(|_| E::custom(format!("{:?} isn't a line number", v)))
+                    }
+                }
+
+                deserializer.deserialize_any(LineNumberVisitor)
+            }
+        }
+
+        struct PoemVisitor;
+
+        impl<'de> Visitor<'de> for PoemVisitor {
+            type Value = Poem;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("numbered lines (1: ..., 2: ...) or a list of them")
+            }
+
+            fn visit_map<M: MapAccess<'de>>(self, mut map: M) -> Result<Self::Value, M::Error> {
+                let mut numbered: Vec<(usize, Verse)> = Vec::new();
+                while let Some((LineNumber(n), verse)) = map.next_entry::<LineNumber, Verse>()? {
+                    numbered.push((n, verse));
+                }
+                numbered.sort_by_key(|(n, _)| *n);
+
+                for (i, (n, _)) in numbered.iter().enumerate() {
+                    if *n == 0 {
+                        return Err(de::Error::custom("lines start at 1"));
+                    }
+                    if *n != i + 1 {
+                        return Err(de::Error::custom(format!("line {} is missing", i + 1)));
+                    }
+                }
+                Poem::checked(numbered.into_iter().map(|(_, verse)| verse).collect())
+            }
+
+            fn visit_seq<S: SeqAccess<'de>>(self, seq: S) -> Result<Self::Value, S::Error> {
+                Poem::checked(Vec::<Verse>::deserialize(SeqAccessDeserializer::new(seq))?)
+            }
+        }
+
+        deserializer.deserialize_any(PoemVisitor)
+    }
+}
+
+/// One line of a tanka: plain text, or the text with its Japanese
+///
+/// ```yaml
+/// 1: "a leaf lands in a puddle."
+/// 5:
+///   text: "that's mono no aware"
+///   ja: "{物|もの}の{哀|あわ}れ"   # readings as {base|reading}
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Verse {
+    /// What the page shows (the English, or the translation when `ja` is the original)
+    pub text: String,
+    /// Japanese, with ruby markup (see [`crate::japanese`])
+    pub ja: Option<String>,
+}
+
+impl Serialize for Verse {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.ja {
+            None => serializer.serialize_str(&self.text),
+            Some(_) => VerseFields {
+                text: self.text.clone(),
+                ja: self.ja.clone(),
+            }
+            .serialize(serializer),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct VerseFields {
+    text: String,
+    #[serde(default)]
+    ja: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for Verse {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::value::MapAccessDeserializer;
+        use serde::de::{self, MapAccess, Visitor};
+
+        struct VerseVisitor;
+
+        impl<'de> Visitor<'de> for VerseVisitor {
+            type Value = Verse;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a verse, or { text: ..., ja: ... }")
+            }
+
+            fn visit_str<E: de::Error>(self, text: &str) -> Result<Self::Value, E> {
+                Ok(Verse {
+                    text: text.to_string(),
+                    ja: None,
+                })
+            }
+
+            // Bare YAML scalars read the same as they would into a String field
+            fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+                self.visit_str("null")
+            }
+
+            fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_map<M: MapAccess<'de>>(self, map: M) -> Result<Self::Value, M::Error> {
+                let fields = VerseFields::deserialize(MapAccessDeserializer::new(map))?;
+                Ok(Verse {
+                    text: fields.text,
+                    ja: fields.ja,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(VerseVisitor)
+    }
+}
+
+/// `<name>.tanka.<lang>.yml`: a tanka's commentary in another language.
+/// Whatever it leaves out shows in the original.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TankaTranslation {
+    #[serde(default)]
+    pub tankadesc: Option<String>,
+    #[serde(default)]
+    pub tastingnotes: Option<String>,
+}
+
+/// A tanka with its slug (URL-safe name)
+#[derive(Debug, Clone)]
+pub struct TankaEntry {
+    pub slug: String,
+    pub filename: String,
+    pub tanka: Tanka,
+    /// Translated commentary by language code, from the tanka's `.<lang>.yml` siblings
+    pub translations: Vec<(String, TankaTranslation)>,
+}
+
+impl TankaEntry {
+    /// Display name, taken from the `[ name ]` part of `top_flavor` (falls back to the slug)
+    pub fn title(&self) -> String {
+        let flavor = &self.tanka.top_flavor;
+        match (flavor.find('['), flavor.rfind(']')) {
+            (Some(start), Some(end)) if start < end => flavor[start + 1..end].trim().to_string(),
+            _ => self.slug.clone(),
+        }
+    }
+
+    /// The tankas most like this one, best first, as build.rs ranked them
+    /// (see `src/related.rs`)
+    pub fn related(&self) -> Vec<TankaEntry> {
+        let tankas = all_tankas();
+        related_files(&self.filename)
+            .iter()
+            .filter_map(|name| tankas.iter().find(|t| t.filename == *name).cloned())
+            .collect()
+    }
+
+    fn translation(&self, lang: &str) -> Option<&TankaTranslation> {
+        self.translations.iter().find(|(l, _)| l == lang).map(|(_, t)| t)
+    }
+
+    /// `tankadesc` in `lang`, or the original
+    pub fn tankadesc(&self, lang: &str) -> &str {
+        self.translation(lang)
+            .and_then(|t| t.tankadesc.as_deref())
+            .unwrap_or(&self.tanka.tankadesc)
+    }
+
+    /// `tastingnotes` in `lang`, or the original
+    pub fn tastingnotes(&self, lang: &str) -> Option<&str> {
+        self.translation(lang)
+            .and_then(|t| t.tastingnotes.as_deref())
+            .or(self.tanka.tastingnotes.as_deref())
+    }
+}
+
+/// Load tanka from YAML string
+pub fn load_tanka(yaml: &str) -> Option<Tanka> {
+    serde_yaml::from_str(yaml).ok()
+}
+
+/// Load a tanka translation from YAML string
+pub fn load_translation(yaml: &str) -> Option<TankaTranslation> {
+    serde_yaml::from_str(yaml).ok()
+}
+
+/// `"mom.tanka.ja.yml"` to `("mom.tanka.yml", "ja")`; `None` for a tanka itself.
+/// build.rs has its own copy of this rule.
+pub fn translation_of(filename: &str) -> Option<(String, &str)> {
+    let (stem, lang) = filename.strip_suffix(".yml")?.rsplit_once('.')?;
+    stem.ends_with(".tanka").then(|| (format!("{}.yml", stem), lang))
+}
+
+/// Convert filename to URL slug
+pub fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .replace(' ', "-")
+        .replace(".tanka.yml", "")
+        .replace(".yml", "")
+}
+
+/// Every tanka, in the book's reading order (`book.yml`). Tankas the book
+/// leaves out come last, in file name order.
+pub fn all_tankas() -> Vec<TankaEntry> {
+    let book = crate::book::book();
+    let entries = book.entries();
+    let mut tankas = content_tankas();
+    tankas.sort_by_key(|t| {
+        entries
+            .iter()
+            .position(|e| matches!(e, crate::book::BookEntry::Tanka(slug) if *slug == t.slug))
+            .unwrap_or(usize::MAX)
+    });
+    tankas
+}
+
+// Auto-generated by build.rs - scans content/ for tankas
+include!(concat!(env!("OUT_DIR"), "/tankas_generated.rs"));
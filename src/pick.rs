@@ -0,0 +1,87 @@
+//! Landing on a tanka without choosing one: `/today`, the same pick for
+//! everyone on a given (UTC) date, and `/random`, which steers clear of what
+//! the reader has just seen
+
+use crate::model::TankaEntry;
+
+/// How many recently seen tankas `/random` avoids (fewer in a short book)
+pub const RECENT_LIMIT: usize = 5;
+
+/// Mixed into the date so the daily order isn't just the day number
+const DAILY_SEED: u64 = 0x6b68_6f6c_6521;
+
+/// Days since 1970-01-01, in UTC
+pub fn today() -> i64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        (js_sys::Date::now() / 86_400_000.0).floor() as i64
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        (secs / 86_400) as i64
+    }
+}
+
+/// `(year, month, day)` of a day number (Howard Hinnant's civil_from_days)
+pub fn civil(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// `2026-10-18`
+pub fn iso_date(days: i64) -> String {
+    let (year, month, day) = civil(days);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Which of `count` tankas is the day's pick: a seeded FNV-1a hash of the
+/// day, so every reader (and the server) agrees without keeping any state
+pub fn daily_index(days: i64, count: usize) -> Option<usize> {
+    if count == 0 {
+        return None;
+    }
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in DAILY_SEED.to_le_bytes().into_iter().chain(days.to_le_bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    Some((hash % count as u64) as usize)
+}
+
+/// The day's tanka, from the book in reading order
+pub fn daily(tankas: &[TankaEntry], days: i64) -> Option<&TankaEntry> {
+    tankas.get(daily_index(days, tankas.len())?)
+}
+
+/// A random slug, avoiding `recent` (most recent last) where the book is long
+/// enough to. `roll` is a number in `[0, 1)`.
+pub fn random_slug(slugs: &[String], recent: &[String], roll: f64) -> Option<String> {
+    // Always leave at least one to choose from, dropping the oldest first
+    let keep = recent.len().min(slugs.len().saturating_sub(1));
+    let avoid = &recent[recent.len() - keep..];
+    let candidates: Vec<&String> = slugs.iter().filter(|s| !avoid.contains(s)).collect();
+    let candidates = if candidates.is_empty() { slugs.iter().collect() } else { candidates };
+
+    let i = ((roll * candidates.len() as f64) as usize).min(candidates.len().checked_sub(1)?);
+    Some(candidates[i].clone())
+}
+
+/// Note that `slug` was just seen, keeping the last [`RECENT_LIMIT`]
+pub fn remember(recent: &mut Vec<String>, slug: &str) {
+    recent.retain(|s| s != slug);
+    recent.push(slug.to_string());
+    let excess = recent.len().saturating_sub(RECENT_LIMIT);
+    recent.drain(..excess);
+}
@@ -0,0 +1,170 @@
+//! "See also": which tankas are most like each other
+//!
+//! build.rs scores every pair once and compiles the top few into the site, so
+//! a page only looks its list up. Shared tags count most, then a shared artist
+//! or album, then how alike the words are (TF-IDF over the verses and
+//! commentary, compared by cosine similarity).
+//!
+//! build.rs includes this file directly, so it only uses std and serde_yaml.
+//! Pages only read the compiled lists, so the reader in the browser doesn't
+//! carry any of it.
+
+use std::collections::{HashMap, HashSet};
+
+/// How many related tankas a page shows
+pub const RELATED_COUNT: usize = 3;
+
+const TAG_WEIGHT: f64 = 1.0;
+const ARTIST_WEIGHT: f64 = 1.5;
+const ALBUM_WEIGHT: f64 = 1.0;
+const TEXT_WEIGHT: f64 = 2.0;
+
+/// Words too common to say anything about a poem
+const STOP_WORDS: &[&str] = &[
+    "a", "about", "all", "an", "and", "are", "as", "at", "be", "been", "but", "by", "can", "do", "for", "from", "had",
+    "has", "have", "he", "her", "his", "i", "if", "in", "into", "is", "it", "its", "just", "like", "me", "more", "my",
+    "no", "not", "of", "on", "or", "our", "out", "she", "so", "some", "that", "the", "their", "them", "there", "they",
+    "this", "to", "too", "up", "very", "was", "we", "were", "what", "when", "which", "who", "with", "you", "your",
+    "tanka", "written", "song",
+];
+
+/// What a tanka is compared on
+#[derive(Debug, Clone, Default)]
+pub struct Features {
+    pub tags: Vec<String>,
+    pub artists: Vec<String>,
+    pub albums: Vec<String>,
+    /// Verses and commentary, as one text
+    pub text: String,
+}
+
+impl Features {
+    /// Read them from a tanka file. Anything missing is just left out; the
+    /// validator reports broken files.
+    pub fn from_yaml(yaml: &str) -> Features {
+        let Ok(tanka) = serde_yaml::from_str::<serde_yaml::Value>(yaml) else {
+            return Features::default();
+        };
+        let str_of = |v: &serde_yaml::Value| v.as_str().map(str::to_string);
+
+        let tags = tanka["tags"].as_sequence().map(|s| s.iter().filter_map(str_of).collect()).unwrap_or_default();
+
+        // One pairing or a setlist of them
+        let pairings: Vec<&serde_yaml::Value> = match &tanka["recommended_music_pairing"] {
+            serde_yaml::Value::Sequence(setlist) => setlist.iter().collect(),
+            pairing => vec![pairing],
+        };
+        let field = |name: &str| -> Vec<String> {
+            pairings.iter().filter_map(|p| str_of(&p[name])).map(|s| s.to_lowercase()).collect()
+        };
+
+        // Verses are numbered or listed, each a string or `{ text, ja }`
+        let poem = if tanka["poem"].is_null() { &tanka["tanka"] } else { &tanka["poem"] };
+        let verses: Vec<&serde_yaml::Value> = match poem {
+            serde_yaml::Value::Mapping(lines) => lines.values().collect(),
+            serde_yaml::Value::Sequence(lines) => lines.iter().collect(),
+            _ => Vec::new(),
+        };
+        let mut text: Vec<String> = verses.into_iter().filter_map(|v| str_of(v).or_else(|| str_of(&v["text"]))).collect();
+        text.extend(["tankadesc", "tastingnotes"].iter().filter_map(|f| str_of(&tanka[*f])));
+
+        Features {
+            tags,
+            artists: field("artist"),
+            albums: field("album"),
+            text: text.join("\n"),
+        }
+    }
+}
+
+/// Lowercase words, without stop words and the odd letter
+pub fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|w| w.chars().count() > 1 && !STOP_WORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// TF-IDF vector per document: term frequency weighted by how rare the term
+/// is across all of them
+fn tf_idf(texts: &[&str]) -> Vec<HashMap<String, f64>> {
+    let docs: Vec<Vec<String>> = texts.iter().map(|t| words(t)).collect();
+
+    let mut doc_freq: HashMap<&str, usize> = HashMap::new();
+    for doc in &docs {
+        for word in doc.iter().map(String::as_str).collect::<HashSet<_>>() {
+            *doc_freq.entry(word).or_default() += 1;
+        }
+    }
+
+    let n = docs.len() as f64;
+    docs.iter()
+        .map(|doc| {
+            let mut counts: HashMap<String, f64> = HashMap::new();
+            for word in doc {
+                *counts.entry(word.clone()).or_default() += 1.0;
+            }
+            let len = doc.len().max(1) as f64;
+            counts
+                .into_iter()
+                .map(|(word, count)| {
+                    let idf = (n / doc_freq[word.as_str()] as f64).ln();
+                    (word, count / len * idf)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn cosine(a: &HashMap<String, f64>, b: &HashMap<String, f64>) -> f64 {
+    let dot: f64 = a.iter().filter_map(|(w, x)| b.get(w).map(|y| x * y)).sum();
+    let norm = |v: &HashMap<String, f64>| v.values().map(|x| x * x).sum::<f64>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+fn shared(a: &[String], b: &[String]) -> usize {
+    a.iter().collect::<HashSet<_>>().intersection(&b.iter().collect()).count()
+}
+
+/// How alike every pair is: `scores[i][j]`, 0 on the diagonal
+pub fn similarity(features: &[Features]) -> Vec<Vec<f64>> {
+    let vectors = tf_idf(&features.iter().map(|f| f.text.as_str()).collect::<Vec<_>>());
+
+    features
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            features
+                .iter()
+                .enumerate()
+                .map(|(j, b)| {
+                    if i == j {
+                        return 0.0;
+                    }
+                    TAG_WEIGHT * shared(&a.tags, &b.tags) as f64
+                        + ARTIST_WEIGHT * (shared(&a.artists, &b.artists) > 0) as u8 as f64
+                        + ALBUM_WEIGHT * (shared(&a.albums, &b.albums) > 0) as u8 as f64
+                        + TEXT_WEIGHT * cosine(&vectors[i], &vectors[j])
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The indices of the `count` most similar to each, best first. Ties keep
+/// the input order, and nothing with nothing in common is listed.
+pub fn related(features: &[Features], count: usize) -> Vec<Vec<usize>> {
+    similarity(features)
+        .into_iter()
+        .map(|scores| {
+            let mut ranked: Vec<(usize, f64)> = scores.into_iter().enumerate().filter(|(_, s)| *s > 0.0).collect();
+            ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+            ranked.into_iter().take(count).map(|(j, _)| j).collect()
+        })
+        .collect()
+}
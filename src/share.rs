@@ -0,0 +1,31 @@
+//! Linking to one verse and quoting it
+//!
+//! A verse link is `/tanka/<slug>?v=3#v3`: the fragment is for the page, which
+//! scrolls to the verse and highlights it, and the query is for the server,
+//! which never sees the fragment but puts just that verse on the link card.
+
+/// Query parameter carrying the verse number
+pub const VERSE_PARAM: &str = "v";
+
+/// `v3`, the id of a verse on its page
+pub fn verse_id(n: usize) -> String {
+    format!("v{}", n)
+}
+
+/// `?v=3#v3`, a verse's link relative to its page
+pub fn verse_link(n: usize) -> String {
+    format!("?{}={}#{}", VERSE_PARAM, n, verse_id(n))
+}
+
+/// The verse number in a fragment (`#v3` or `v3`) or a `?v=` value (`3`)
+pub fn verse_number(s: &str) -> Option<usize> {
+    let s = s.strip_prefix('#').unwrap_or(s);
+    let s = s.strip_prefix('v').unwrap_or(s);
+    s.parse().ok().filter(|n| *n > 0)
+}
+
+/// What copying a verse puts on the clipboard: the line, whose it is and
+/// where to read the rest
+pub fn quote(line: &str, title: &str, url: &str) -> String {
+    format!("“{}” — {}\n{}", line.trim(), title, url)
+}
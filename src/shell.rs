@@ -0,0 +1,198 @@
+//! The in-page shell: commands over `all_tankas()`
+//!
+//! Parsing, output and tab completion live here so they can be tested without
+//! a browser; `app.rs` only renders [`Output`] and keeps the history.
+
+use crate::model::{TankaEntry, AUTHOR};
+
+/// Every command, for `help` and completion
+pub const COMMANDS: &[(&str, &str)] = &[
+    ("ls", "list the tankas"),
+    ("cat <tanka>", "print a tanka"),
+    ("grep <word>", "search verses, commentary and pairings"),
+    ("play <tanka>", "play a tanka's music pairing"),
+    ("random", "print a random tanka"),
+    ("whoami", "who wrote this"),
+    ("tree", "show the content directory"),
+    ("clear", "clear the screen"),
+    ("help", "this list"),
+];
+
+/// Commands whose argument is a tanka, and so complete slugs
+const TAKES_TANKA: &[&str] = &["cat", "play"];
+
+/// What a command printed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Output {
+    Nothing,
+    Text(Vec<String>),
+    /// Lines that link to a tanka: `(text, slug)`, with an empty slug for plain lines
+    Listing(Vec<(String, String)>),
+    /// A tanka printed inline, by slug
+    Tanka(String),
+    /// A tanka's pairing played inline, by slug
+    Play(String),
+    Error(String),
+    /// Wipe the scrollback
+    Clear,
+}
+
+/// Run one command line. `random` is a number in `[0, 1)` for the `random` command.
+pub fn run(line: &str, tankas: &[TankaEntry], random: f64) -> Output {
+    let line = line.trim();
+    let (command, arg) = match line.split_once(char::is_whitespace) {
+        Some((command, arg)) => (command, arg.trim()),
+        None => (line, ""),
+    };
+
+    match command {
+        "" => Output::Nothing,
+        "help" => Output::Text(
+            COMMANDS
+                .iter()
+                .map(|(usage, about)| format!("{:<14}{}", usage, about))
+                .collect(),
+        ),
+        "ls" => Output::Listing(
+            tankas
+                .iter()
+                .map(|t| (t.filename.clone(), t.slug.clone()))
+                .collect(),
+        ),
+        "cat" | "play" if arg.is_empty() => Output::Error(format!("usage: {} <tanka>", command)),
+        "cat" => match find(tankas, arg) {
+            Some(entry) => Output::Tanka(entry.slug.clone()),
+            None => Output::Error(format!("cat: {}: No such file or directory", arg)),
+        },
+        "play" => match find(tankas, arg) {
+            Some(entry) => Output::Play(entry.slug.clone()),
+            None => Output::Error(format!("play: {}: No such file or directory", arg)),
+        },
+        "grep" if arg.is_empty() => Output::Error("usage: grep <word>".to_string()),
+        "grep" => grep(tankas, arg),
+        "random" if tankas.is_empty() => Output::Error("random: no tankas".to_string()),
+        "random" => {
+            let i = ((random * tankas.len() as f64) as usize).min(tankas.len() - 1);
+            Output::Tanka(tankas[i].slug.clone())
+        }
+        "whoami" => whoami(tankas),
+        "tree" => tree(tankas),
+        "clear" => Output::Clear,
+        _ => Output::Error(format!("{}: command not found", command)),
+    }
+}
+
+/// A tanka by slug, file name or title
+pub fn find<'a>(tankas: &'a [TankaEntry], name: &str) -> Option<&'a TankaEntry> {
+    let name = name.trim_start_matches("content/");
+    tankas
+        .iter()
+        .find(|t| t.slug == name || t.filename == name || t.title() == name)
+        .or_else(|| tankas.iter().find(|t| t.slug == crate::model::slugify(name)))
+}
+
+/// Case-insensitive search, one line per match as `slug:field: text`
+fn grep(tankas: &[TankaEntry], word: &str) -> Output {
+    let needle = word.to_lowercase();
+    let mut matches = Vec::new();
+
+    for entry in tankas {
+        let tanka = &entry.tanka;
+        let mut fields: Vec<(String, &str)> = tanka
+            .tanka
+            .as_vec()
+            .into_iter()
+            .enumerate()
+            .map(|(i, verse)| ((i + 1).to_string(), verse))
+            .collect();
+        for pairing in tanka.recommended_music_pairing.iter() {
+            fields.push(("track".to_string(), &pairing.track));
+            fields.push(("artist".to_string(), &pairing.artist));
+            fields.push(("album".to_string(), &pairing.album));
+        }
+        fields.push(("tankadesc".to_string(), &tanka.tankadesc));
+        if let Some(notes) = &tanka.tastingnotes {
+            fields.push(("tastingnotes".to_string(), notes));
+        }
+
+        for (field, text) in fields {
+            if let Some(line) = text.lines().find(|l| l.to_lowercase().contains(&needle)) {
+                matches.push((format!("{}:{}: {}", entry.slug, field, line.trim()), entry.slug.clone()));
+            }
+        }
+    }
+
+    if matches.is_empty() {
+        Output::Error(format!("grep: no matches for {:?}", word))
+    } else {
+        Output::Listing(matches)
+    }
+}
+
+fn whoami(tankas: &[TankaEntry]) -> Output {
+    let mut lines = vec![format!("{} - author", AUTHOR)];
+
+    let mut guests: Vec<(String, Vec<String>)> = Vec::new();
+    for entry in tankas {
+        let Some(guest) = &entry.tanka.guest_contributor else { continue };
+        match guests.iter_mut().find(|(name, _)| *name == guest.name) {
+            Some((_, titles)) => titles.push(entry.title()),
+            None => guests.push((guest.name.clone(), vec![entry.title()])),
+        }
+    }
+    for (name, titles) in guests {
+        lines.push(format!("{} - guest contributor ({})", name, titles.join(", ")));
+    }
+
+    Output::Text(lines)
+}
+
+fn tree(tankas: &[TankaEntry]) -> Output {
+    let mut lines = vec![("content/".to_string(), String::new())];
+    for (i, entry) in tankas.iter().enumerate() {
+        let branch = if i + 1 == tankas.len() { "└── " } else { "├── " };
+        lines.push((format!("{}{}", branch, entry.filename), entry.slug.clone()));
+    }
+    lines.push((format!("0 directories, {} files", tankas.len()), String::new()));
+    Output::Listing(lines)
+}
+
+/// Tab completion: the line extended as far as every candidate agrees, and the
+/// candidates to list when there's more than one
+pub fn complete(line: &str, tankas: &[TankaEntry]) -> (String, Vec<String>) {
+    let (prefix, partial, candidates): (String, &str, Vec<String>) = match line.split_once(' ') {
+        None => (
+            String::new(),
+            line,
+            COMMANDS
+                .iter()
+                .map(|(usage, _)| usage.split(' ').next().unwrap_or_default().to_string())
+                .collect(),
+        ),
+        Some((command, arg)) if TAKES_TANKA.contains(&command) => (
+            format!("{} ", command),
+            arg.trim_start(),
+            tankas.iter().map(|t| t.slug.clone()).collect(),
+        ),
+        Some(_) => return (line.to_string(), Vec::new()),
+    };
+
+    let matching: Vec<String> = candidates.into_iter().filter(|c| c.starts_with(partial)).collect();
+    match matching.as_slice() {
+        [] => (line.to_string(), Vec::new()),
+        [only] => (format!("{}{} ", prefix, only), Vec::new()),
+        _ => {
+            let common = common_prefix(&matching);
+            (format!("{}{}", prefix, common), matching)
+        }
+    }
+}
+
+fn common_prefix(words: &[String]) -> String {
+    let mut prefix: Vec<char> = words[0].chars().collect();
+    for word in &words[1..] {
+        let shared = prefix.iter().zip(word.chars()).take_while(|(a, b)| **a == *b).count();
+        prefix.truncate(shared);
+    }
+    prefix.into_iter().collect()
+}
@@ -0,0 +1,189 @@
+//! The book's music pairings as M3U, XSPF and JSPF playlists (native only)
+//!
+//! Written next to the built site by the `soundtrack` binary, and linked from
+//! the index page.
+
+use serde_json::json;
+
+use crate::embed::{youtube_video_id, AUDIO_URL_PREFIX};
+use crate::model::TankaEntry;
+
+/// File names the playlists are written under, and linked from the index
+pub const M3U_FILE: &str = "soundtrack.m3u8";
+pub const XSPF_FILE: &str = "soundtrack.xspf";
+pub const JSPF_FILE: &str = "soundtrack.jspf";
+
+const PLAYLIST_TITLE: &str = "Digital K-Hole - the soundtrack";
+
+/// One pairing, ready to be written in any format
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundtrackTrack {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    /// Where to listen, best first (self-hosted audio, Bandcamp, SoundCloud, YouTube)
+    pub locations: Vec<String>,
+    pub slug: String,
+    /// Page of the tanka this pairing belongs to
+    pub info: String,
+    /// "tanka: <slug>", plus the pairing's note if it has one
+    pub annotation: String,
+}
+
+/// Every pairing in book order. `base_url` (e.g. `https://example.com`, or empty)
+/// is prepended to links into the site itself.
+pub fn soundtrack(tankas: &[TankaEntry], base_url: &str) -> Vec<SoundtrackTrack> {
+    let base_url = base_url.trim_end_matches('/');
+
+    tankas
+        .iter()
+        .flat_map(|entry| {
+            entry
+                .tanka
+                .pairings()
+                .into_iter()
+                .filter(|p| !is_placeholder(&p.track))
+                .map(move |pairing| {
+                    let mut locations = Vec::new();
+                    if let Some(audio) = &pairing.audio {
+                        if audio.starts_with(AUDIO_URL_PREFIX) {
+                            locations.push(format!("{}{}", base_url, audio));
+                        } else {
+                            locations.push(audio.clone());
+                        }
+                    }
+                    let bandcamp = pairing.bandcamp.clone().or_else(|| {
+                        pairing
+                            .qr_link
+                            .clone()
+                            .filter(|link| link.contains("bandcamp.com/"))
+                    });
+                    locations.extend(bandcamp);
+                    locations.extend(pairing.soundcloud.clone());
+                    if let Some(Ok(id)) = pairing.youtube.as_deref().map(youtube_video_id) {
+                        locations.push(format!("https://www.youtube.com/watch?v={}", id));
+                    }
+
+                    let mut annotation = format!("tanka: {}", entry.slug);
+                    if let Some(note) = &pairing.note {
+                        annotation.push_str(&format!(" - {}", note));
+                    }
+
+                    SoundtrackTrack {
+                        title: pairing.track.clone(),
+                        artist: clean(&pairing.artist),
+                        album: clean(&pairing.album),
+                        locations,
+                        slug: entry.slug.clone(),
+                        info: format!("{}/tanka/{}", base_url, entry.slug),
+                        annotation,
+                    }
+                })
+        })
+        .collect()
+}
+
+/// Pairings not chosen yet are written as `null` in YAML
+fn is_placeholder(value: &str) -> bool {
+    value.is_empty() || value == "null"
+}
+
+fn clean(value: &str) -> String {
+    if is_placeholder(value) {
+        String::new()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Extended M3U, one location per entry. Tracks with nowhere to listen are
+/// kept as comments pointing at their tanka, so every format lists the same
+/// tracks without a player reading a location-less entry.
+pub fn to_m3u(tracks: &[SoundtrackTrack]) -> String {
+    let mut m3u = String::from("#EXTM3U\n");
+    m3u.push_str(&format!("#PLAYLIST:{}\n", PLAYLIST_TITLE));
+
+    for track in tracks {
+        let location = track.locations.first();
+        let prefix = if location.is_some() { "" } else { "# " };
+        m3u.push('\n');
+        m3u.push_str(&format!("# {}\n", track.annotation));
+        if location.is_none() {
+            m3u.push_str("# nowhere to listen online yet\n");
+        }
+        m3u.push_str(&format!("{}#EXTINF:-1,{} - {}\n", prefix, one_line(&track.artist), one_line(&track.title)));
+        if !track.album.is_empty() {
+            m3u.push_str(&format!("{}#EXTALB:{}\n", prefix, one_line(&track.album)));
+        }
+        m3u.push_str(&format!("{}{}\n", prefix, location.unwrap_or(&track.info)));
+    }
+
+    m3u
+}
+
+/// XSPF (https://xspf.org/spec)
+pub fn to_xspf(tracks: &[SoundtrackTrack]) -> String {
+    let mut xspf = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xspf.push_str("<playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n");
+    xspf.push_str(&format!("  <title>{}</title>\n", escape_xml(PLAYLIST_TITLE)));
+    xspf.push_str("  <trackList>\n");
+
+    for track in tracks {
+        xspf.push_str("    <track>\n");
+        for location in &track.locations {
+            xspf.push_str(&format!("      <location>{}</location>\n", escape_xml(location)));
+        }
+        xspf.push_str(&format!("      <title>{}</title>\n", escape_xml(&track.title)));
+        if !track.artist.is_empty() {
+            xspf.push_str(&format!("      <creator>{}</creator>\n", escape_xml(&track.artist)));
+        }
+        if !track.album.is_empty() {
+            xspf.push_str(&format!("      <album>{}</album>\n", escape_xml(&track.album)));
+        }
+        xspf.push_str(&format!("      <annotation>{}</annotation>\n", escape_xml(&track.annotation)));
+        xspf.push_str(&format!("      <info>{}</info>\n", escape_xml(&track.info)));
+        xspf.push_str("    </track>\n");
+    }
+
+    xspf.push_str("  </trackList>\n");
+    xspf.push_str("</playlist>\n");
+    xspf
+}
+
+/// JSPF, the JSON form of XSPF
+pub fn to_jspf(tracks: &[SoundtrackTrack]) -> String {
+    let tracks: Vec<_> = tracks
+        .iter()
+        .map(|track| {
+            json!({
+                "location": track.locations,
+                "title": track.title,
+                "creator": track.artist,
+                "album": track.album,
+                "annotation": track.annotation,
+                "info": track.info,
+            })
+        })
+        .collect();
+
+    let playlist = json!({
+        "playlist": {
+            "title": PLAYLIST_TITLE,
+            "track": tracks,
+        }
+    });
+
+    serde_json::to_string_pretty(&playlist).unwrap_or_default() + "\n"
+}
+
+pub(crate) fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// M3U is line based, so no newlines inside a field
+fn one_line(s: &str) -> String {
+    s.replace(['\r', '\n'], " ")
+}
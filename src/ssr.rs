@@ -0,0 +1,72 @@
+//! Server-side rendering of the reader, used by `serve` when built with `--features ssr`
+//!
+//! The HTML shell is trunk's `dist/index.html` (built from `index.hydrate.html`),
+//! so the hashed script and stylesheet names always match the WASM bundle that
+//! hydrates the page. Preparing the shell needs no rendering, so it's built
+//! without the feature too, for the tests.
+
+#[cfg(feature = "ssr")]
+use futures::StreamExt;
+#[cfg(feature = "ssr")]
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_meta::ServerMetaContext;
+#[cfg(feature = "ssr")]
+use leptos_router::location::RequestUrl;
+
+#[cfg(feature = "ssr")]
+use crate::app::App;
+
+/// Globals the hydrate bundle reads on startup. Normally written by leptos'
+/// `HydrationScripts`, which we don't use because trunk owns the script tags.
+pub const HYDRATION_GLOBALS: &str =
+    "<script>__RESOLVED_RESOURCES=[];__SERIALIZED_ERRORS=[];__INCOMPLETE_CHUNKS=[];</script>";
+
+/// Marker leptos_meta hydrates `<meta>` tags from, must sit right before `</head>`
+const HEAD_MARKER: &str = "<!--HEAD-->";
+
+/// Turn trunk's index.html into a template for [`render_page`].
+///
+/// Drops the static `<title>` (each page renders its own) and adds the
+/// hydration globals and leptos_meta head marker.
+pub fn prepare_shell(index_html: &str) -> String {
+    let mut shell = index_html.to_string();
+
+    if let (Some(start), Some(end)) = (shell.find("<title>"), shell.find("</title>")) {
+        shell.replace_range(start..end + "</title>".len(), "");
+    }
+
+    if let Some(head_end) = shell.find("</head>") {
+        shell.insert_str(head_end, &format!("{}{}", HYDRATION_GLOBALS, HEAD_MARKER));
+    }
+
+    shell
+}
+
+/// Render the page at `url` (path and query) into the shell, with its
+/// `<title>` and meta tags in `<head>`
+#[cfg(feature = "ssr")]
+pub async fn render_page(shell: &str, url: &str) -> String {
+    let owner = Owner::new();
+    let (body, meta_output) = owner.with(|| {
+        provide_context(RequestUrl::new(url));
+        let (meta_context, meta_output) = ServerMetaContext::new();
+        provide_context(meta_context);
+
+        (view! { <App /> }.to_html(), meta_output)
+    });
+    drop(owner);
+
+    // hydrate_body() starts from the first child of <body>, so no whitespace in between
+    let mut page = shell.to_string();
+    match page.find("<body").and_then(|i| page[i..].find('>').map(|j| i + j + 1)) {
+        Some(body_start) => page.insert_str(body_start, &body),
+        None => page.push_str(&body),
+    }
+
+    meta_output
+        .inject_meta_context(futures::stream::once(async move { page }).boxed())
+        .await
+        .collect::<String>()
+        .await
+}
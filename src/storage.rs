@@ -0,0 +1,271 @@
+//! What the reader keeps in their browser: favorites, where they left off,
+//! what they've read and what they saw last, and the choices made in the
+//! settings panels
+//!
+//! Everything lives in localStorage under versioned, namespaced keys
+//! (`dkh:v1:favorites`), so a later format can move to `v2` and migrate,
+//! and can be exported as JSON and imported in another browser.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Prefix of every key this module writes
+pub const NAMESPACE: &str = "dkh";
+
+/// Version of the stored values and of the export format
+pub const VERSION: u32 = 1;
+
+/// `format` in an export, so an import can tell it's ours
+pub const EXPORT_FORMAT: &str = "digital-khole";
+
+/// How a value was written before values were kept as JSON
+#[derive(Clone, Copy)]
+enum Legacy {
+    /// A plain string: `dark`
+    Text,
+    /// Comma-separated: `a,b`
+    List,
+    /// Already valid JSON: `true`, `15`
+    Json,
+}
+
+/// Keys from before the namespace, moved over once by [`Store::migrate`]
+const LEGACY_KEYS: &[(&str, &str, Legacy)] = &[
+    ("age_verified", "age_verified", Legacy::Json),
+    ("cw_mode", "cw_mode", Legacy::Text),
+    ("cw_categories", "cw_categories", Legacy::List),
+    ("listening_session", "listening_session", Legacy::Json),
+    ("theme", "theme", Legacy::Text),
+    ("locale", "locale", Legacy::Text),
+];
+
+/// `dkh:v1:<name>`
+pub fn key(name: &str) -> String {
+    format!("{}:v{}:{}", NAMESPACE, VERSION, name)
+}
+
+/// localStorage, through namespaced keys holding JSON
+pub struct Store {
+    storage: web_sys::Storage,
+}
+
+impl Store {
+    /// The browser's localStorage, `None` when it's blocked. Browser only, so
+    /// call it from effects and event handlers.
+    pub fn open() -> Option<Store> {
+        let storage = web_sys::window()?.local_storage().ok().flatten()?;
+        Some(Store { storage })
+    }
+
+    pub fn get<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        let json = self.storage.get(&key(name)).ok().flatten()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    pub fn set<T: Serialize>(&self, name: &str, value: &T) {
+        if let Ok(json) = serde_json::to_string(value) {
+            let _ = self.storage.set(&key(name), &json);
+        }
+    }
+
+    pub fn remove(&self, name: &str) {
+        let _ = self.storage.remove_item(&key(name));
+    }
+
+    /// `set` when there's a value, `remove` when there isn't
+    pub fn set_or_remove<T: Serialize>(&self, name: &str, value: Option<&T>) {
+        match value {
+            Some(value) => self.set(name, value),
+            None => self.remove(name),
+        }
+    }
+
+    /// Move values stored before the namespace existed under their new keys
+    pub fn migrate(&self) {
+        for (old, new, legacy) in LEGACY_KEYS {
+            let Some(value) = self.storage.get(old).ok().flatten() else {
+                continue;
+            };
+            if self.storage.get(&key(new)).ok().flatten().is_none() {
+                match legacy {
+                    Legacy::Text => self.set(new, &value),
+                    Legacy::List => {
+                        let list: Vec<&str> = value.split(',').filter(|s| !s.is_empty()).collect();
+                        self.set(new, &list);
+                    }
+                    Legacy::Json => {
+                        if serde_json::from_str::<serde_json::Value>(&value).is_ok() {
+                            let _ = self.storage.set(&key(new), &value);
+                        }
+                    }
+                }
+            }
+            let _ = self.storage.remove_item(old);
+        }
+    }
+}
+
+/// Everything kept about the reader
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReaderData {
+    /// Slugs, in the order they were favorited
+    pub favorites: Vec<String>,
+    /// Slugs opened (or marked read), in the order they were first read
+    pub read: Vec<String>,
+    /// The tanka to resume at
+    pub last_read: Option<String>,
+    /// The last few opened, oldest first, for `/random` to avoid
+    pub recently_seen: Vec<String>,
+    /// Age gate, content warnings, session, theme and language
+    pub settings: Settings,
+}
+
+/// The choices made at the age gate and in the settings panels, as stored:
+/// the app turns them into its own types
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// The reader confirmed their age
+    pub age_verified: bool,
+    /// Content warnings: `show`, `hide` or `custom` (hide when unset)
+    pub cw_mode: Option<String>,
+    /// Categories blurred in `custom` mode
+    pub cw_categories: Vec<String>,
+    /// Pause in seconds between tracks while a listening session runs
+    pub listening_session: Option<u32>,
+    /// Theme name, following the system when unset
+    pub theme: Option<String>,
+    /// Locale code, following the browser when unset
+    pub locale: Option<String>,
+}
+
+impl Settings {
+    /// Read them from localStorage; defaults where there's none
+    pub fn load() -> Settings {
+        let Some(store) = Store::open() else {
+            return Settings::default();
+        };
+        store.migrate();
+        Settings::read(&store)
+    }
+
+    fn read(store: &Store) -> Settings {
+        Settings {
+            age_verified: store.get("age_verified").unwrap_or_default(),
+            cw_mode: store.get("cw_mode"),
+            cw_categories: store.get("cw_categories").unwrap_or_default(),
+            listening_session: store.get("listening_session"),
+            theme: store.get("theme"),
+            locale: store.get("locale"),
+        }
+    }
+
+    fn write(&self, store: &Store) {
+        store.set("age_verified", &self.age_verified);
+        store.set_or_remove("cw_mode", self.cw_mode.as_ref());
+        store.set("cw_categories", &self.cw_categories);
+        store.set_or_remove("listening_session", self.listening_session.as_ref());
+        store.set_or_remove("theme", self.theme.as_ref());
+        store.set_or_remove("locale", self.locale.as_ref());
+    }
+}
+
+/// An export: the data, and which format and version wrote it
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Export {
+    format: String,
+    version: u32,
+    reader: ReaderData,
+}
+
+impl ReaderData {
+    /// Read it from localStorage; empty where there's none
+    pub fn load() -> ReaderData {
+        let Some(store) = Store::open() else {
+            return ReaderData::default();
+        };
+        store.migrate();
+
+        ReaderData {
+            favorites: store.get("favorites").unwrap_or_default(),
+            read: store.get("read").unwrap_or_default(),
+            last_read: store.get("last_read"),
+            recently_seen: store.get("recently_seen").unwrap_or_default(),
+            settings: Settings::read(&store),
+        }
+    }
+
+    pub fn save(&self) {
+        let Some(store) = Store::open() else { return };
+
+        store.set("favorites", &self.favorites);
+        store.set("read", &self.read);
+        store.set_or_remove("last_read", self.last_read.as_ref());
+        store.set("recently_seen", &self.recently_seen);
+        self.settings.write(&store);
+    }
+
+    pub fn is_favorite(&self, slug: &str) -> bool {
+        self.favorites.iter().any(|s| s == slug)
+    }
+
+    pub fn is_read(&self, slug: &str) -> bool {
+        self.read.iter().any(|s| s == slug)
+    }
+
+    pub fn toggle_favorite(&mut self, slug: &str) {
+        if self.is_favorite(slug) {
+            self.favorites.retain(|s| s != slug);
+        } else {
+            self.favorites.push(slug.to_string());
+        }
+    }
+
+    pub fn set_read(&mut self, slug: &str, read: bool) {
+        if !read {
+            self.read.retain(|s| s != slug);
+        } else if !self.is_read(slug) {
+            self.read.push(slug.to_string());
+        }
+    }
+
+    /// Opening a tanka: it's read, and where to resume
+    pub fn open(&mut self, slug: &str) {
+        self.set_read(slug, true);
+        self.last_read = Some(slug.to_string());
+        crate::pick::remember(&mut self.recently_seen, slug);
+    }
+
+    /// Forget slugs that aren't in the book (any more)
+    pub fn retain_known(&mut self, slugs: &[String]) {
+        let known = |s: &String| slugs.contains(s);
+        self.favorites.retain(known);
+        self.read.retain(known);
+        self.recently_seen.retain(known);
+        self.last_read = self.last_read.take().filter(known);
+    }
+
+    /// Pretty-printed JSON, for the reader to save somewhere
+    pub fn to_json(&self) -> String {
+        let export = Export {
+            format: EXPORT_FORMAT.to_string(),
+            version: VERSION,
+            reader: self.clone(),
+        };
+        serde_json::to_string_pretty(&export).unwrap_or_default()
+    }
+
+    /// Read an export back, refusing other files and newer versions
+    pub fn from_json(json: &str) -> Result<ReaderData, String> {
+        let export: Export = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if export.format != EXPORT_FORMAT {
+            return Err(format!("not a {} export", EXPORT_FORMAT));
+        }
+        if export.version > VERSION {
+            return Err(format!("made by a newer version (v{}, this is v{})", export.version, VERSION));
+        }
+        Ok(export.reader)
+    }
+}
@@ -0,0 +1,122 @@
+//! Tags: the themes poems share, from the controlled vocabulary in `tags.yml`
+//!
+//! A tanka's `tags:` may only use names listed there; `just validate` fails on
+//! anything else and suggests the tag that was probably meant.
+
+use crate::model::TankaEntry;
+
+/// One theme in the vocabulary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    /// Shown under the tag on its page
+    pub about: String,
+}
+
+impl Tag {
+    pub fn href(&self) -> String {
+        format!("/tag/{}", self.name)
+    }
+}
+
+/// Read a vocabulary: a map of tag name to what it means, in file order
+/// (a file with nothing but comments is an empty vocabulary)
+pub fn parse_vocabulary(yaml: &str) -> Result<Vec<Tag>, String> {
+    let map: Option<serde_yaml::Mapping> = serde_yaml::from_str(yaml).map_err(|e| e.to_string())?;
+    map.unwrap_or_default()
+        .into_iter()
+        .map(|(name, about)| match (name, about) {
+            (serde_yaml::Value::String(name), serde_yaml::Value::String(about)) => Ok(Tag { name, about }),
+            (name, _) => Err(format!("{:?}: expected `<tag>: <what it means>`", name)),
+        })
+        .collect()
+}
+
+/// `tags.yml`, compiled in. A broken file reads as no tags; the validator says why.
+pub fn vocabulary() -> Vec<Tag> {
+    parse_vocabulary(include_str!("../tags.yml")).unwrap_or_default()
+}
+
+/// The vocabulary entry for `name`
+pub fn tag(name: &str) -> Option<Tag> {
+    vocabulary().into_iter().find(|t| t.name == name)
+}
+
+/// Lowercase letters and digits, words joined by single hyphens
+pub fn is_well_formed(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .split('-')
+            .all(|word| !word.is_empty() && word.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()))
+}
+
+/// Tankas tagged `name`, in book order
+pub fn tagged<'a>(tankas: &'a [TankaEntry], name: &str) -> Vec<&'a TankaEntry> {
+    tankas.iter().filter(|t| t.tanka.tags.iter().any(|tag| tag == name)).collect()
+}
+
+/// Every tag in the vocabulary with how many tankas use it, in vocabulary order
+pub fn tag_counts(tankas: &[TankaEntry]) -> Vec<(Tag, usize)> {
+    vocabulary()
+        .into_iter()
+        .map(|tag| {
+            let count = tagged(tankas, &tag.name).len();
+            (tag, count)
+        })
+        .collect()
+}
+
+/// Size in the tag cloud, 1 to 5, relative to the most used tag
+pub fn weight(count: usize, max: usize) -> usize {
+    if max == 0 {
+        return 1;
+    }
+    1 + (count * 4).div_ceil(max).min(4)
+}
+
+/// The same tag spelled differently: case, separators and a plural `s` aside
+/// they match, or (for longer names) they're one typo apart
+pub fn near_duplicates(a: &str, b: &str) -> bool {
+    let (a, b) = (normalize(a), normalize(b));
+    a == b || (a.len().min(b.len()) >= 5 && edit_distance(&a, &b) <= 1)
+}
+
+/// The vocabulary tag closest to an unknown `name`, if one is close enough to
+/// have been meant
+pub fn suggest<'a>(name: &str, vocabulary: &'a [Tag]) -> Option<&'a str> {
+    let name = normalize(name);
+    vocabulary
+        .iter()
+        .map(|tag| (edit_distance(&name, &normalize(&tag.name)), tag.name.as_str()))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, tag)| tag)
+}
+
+fn normalize(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect();
+    match name.strip_suffix('s') {
+        Some(singular) if singular.len() > 2 => singular.to_string(),
+        _ => name,
+    }
+}
+
+/// Levenshtein distance, by characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1).min(row[j] + 1).min(diagonal + usize::from(ca != *cb));
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
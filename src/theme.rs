@@ -0,0 +1,116 @@
+//! Reader themes
+//!
+//! `style.css` carries each theme as a set of custom properties (dark and
+//! light as `light-dark()` pairs on `:root`, the rest under
+//! `[data-theme="..."]`). The palettes here cover what CSS can't reach: the
+//! generated QR codes, the favicon and the colors handed to embedded players.
+//! Keep the two in step.
+
+use std::fmt;
+
+use crate::embed::{PlayerColors, SITE_PLAYER_COLORS};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+    /// TempleOS' 16-color palette: blue on white
+    TempleOs,
+}
+
+/// The handful of colors Rust-side rendering needs, as 6-digit hex without `#`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub background: &'static str,
+    pub foreground: &'static str,
+    pub accent: &'static str,
+}
+
+impl Theme {
+    /// Every theme, in the order the switcher lists them
+    pub const ALL: [Theme; 4] = [Theme::Dark, Theme::Light, Theme::HighContrast, Theme::TempleOs];
+
+    /// Value of `data-theme`, of the stored preference and (as `theme-<name>`)
+    /// of the switcher's message in `locales/`
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+            Theme::HighContrast => "high-contrast",
+            Theme::TempleOs => "templeos",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Theme> {
+        Theme::ALL.into_iter().find(|t| t.name() == name)
+    }
+
+    pub fn palette(self) -> Palette {
+        match self {
+            Theme::Dark => Palette {
+                background: SITE_PLAYER_COLORS.background,
+                foreground: "ffffff",
+                accent: SITE_PLAYER_COLORS.link,
+            },
+            Theme::Light => Palette {
+                background: "ffffff",
+                foreground: "000000",
+                accent: "0b57d0",
+            },
+            Theme::HighContrast => Palette {
+                background: "000000",
+                foreground: "ffffff",
+                accent: "00ffff",
+            },
+            Theme::TempleOs => Palette {
+                background: "ffffff",
+                foreground: "0000aa",
+                accent: "aa00aa",
+            },
+        }
+    }
+
+    /// Colors for the Bandcamp and SoundCloud players
+    pub fn player_colors(self) -> PlayerColors {
+        let palette = self.palette();
+        PlayerColors {
+            background: palette.background,
+            link: palette.accent,
+        }
+    }
+
+    /// `(modules, background)` for QR codes. Always the darker color on the
+    /// lighter one, since plenty of scanners can't read inverted codes.
+    pub fn qr_colors(self) -> ([u8; 3], [u8; 3]) {
+        let palette = self.palette();
+        let (fg, bg) = (rgb(palette.foreground), rgb(palette.background));
+        if luminance(fg) <= luminance(bg) {
+            (fg, bg)
+        } else {
+            (bg, fg)
+        }
+    }
+
+    /// Color the favicon's noise is tinted with (the hole itself stays black)
+    pub fn favicon_tint(self) -> [u8; 3] {
+        rgb(self.palette().foreground)
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// `"4d96ff"` to `[0x4d, 0x96, 0xff]` (palettes are constants, so no error path)
+fn rgb(hex: &str) -> [u8; 3] {
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0);
+    [channel(0), channel(2), channel(4)]
+}
+
+fn luminance([r, g, b]: [u8; 3]) -> u32 {
+    299 * u32::from(r) + 587 * u32::from(g) + 114 * u32::from(b)
+}
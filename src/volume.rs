@@ -0,0 +1,145 @@
+//! `volume_level` on a five-step scale
+//!
+//! Accepts the scale names, the phrasings content already used ("medium",
+//! "very high", ...), or a custom label placed on the scale:
+//!
+//! ```yaml
+//! volume_level: loud
+//! volume_level: { custom: "YOU'LL GO DEAF", meter: 5 }
+//! ```
+//!
+//! Anything else is kept as free text without a meter, and reported by the validator.
+
+use std::fmt;
+
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Number of steps on the meter
+pub const METER_STEPS: u8 = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VolumeLevel {
+    Whisper,
+    Quiet,
+    Comfortable,
+    Loud,
+    ExtremelyLoud,
+    /// Wording the scale doesn't cover. `meter` is 1-5 when given in map form,
+    /// `None` for unrecognised plain text.
+    Custom { text: String, meter: Option<u8> },
+}
+
+impl VolumeLevel {
+    /// Every step of the scale, quietest first
+    pub const SCALE: [VolumeLevel; 5] = [
+        VolumeLevel::Whisper,
+        VolumeLevel::Quiet,
+        VolumeLevel::Comfortable,
+        VolumeLevel::Loud,
+        VolumeLevel::ExtremelyLoud,
+    ];
+
+    /// Read a plain-text volume, accepting the synonyms existing content uses
+    pub fn parse(text: &str) -> Self {
+        match text.trim().to_lowercase().as_str() {
+            "whisper" | "very quiet" | "barely audible" => VolumeLevel::Whisper,
+            "quiet" | "low" | "soft" => VolumeLevel::Quiet,
+            "comfortable" | "medium" | "normal" | "moderate" => VolumeLevel::Comfortable,
+            "loud" | "high" | "medium-high" => VolumeLevel::Loud,
+            "extremely loud" | "very loud" | "very high" | "max" => VolumeLevel::ExtremelyLoud,
+            _ => VolumeLevel::Custom {
+                text: text.to_string(),
+                meter: None,
+            },
+        }
+    }
+
+    /// Text shown to readers
+    pub fn label(&self) -> &str {
+        match self {
+            VolumeLevel::Whisper => "whisper",
+            VolumeLevel::Quiet => "quiet",
+            VolumeLevel::Comfortable => "comfortable",
+            VolumeLevel::Loud => "loud",
+            VolumeLevel::ExtremelyLoud => "extremely loud",
+            VolumeLevel::Custom { text, .. } => text,
+        }
+    }
+
+    /// Position on the meter, 1 (whisper) to 5 (extremely loud)
+    pub fn meter(&self) -> Option<u8> {
+        match self {
+            VolumeLevel::Whisper => Some(1),
+            VolumeLevel::Quiet => Some(2),
+            VolumeLevel::Comfortable => Some(3),
+            VolumeLevel::Loud => Some(4),
+            VolumeLevel::ExtremelyLoud => Some(5),
+            VolumeLevel::Custom { meter, .. } => *meter,
+        }
+    }
+
+    /// Player volume (0.0-1.0) to suggest or apply for this level
+    pub fn player_volume(&self) -> Option<f64> {
+        self.meter().map(|m| f64::from(m) / f64::from(METER_STEPS))
+    }
+}
+
+impl fmt::Display for VolumeLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl Serialize for VolumeLevel {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            VolumeLevel::Custom { text, meter: Some(meter) } => {
+                CustomForm { custom: text.clone(), meter: *meter }.serialize(serializer)
+            }
+            _ => serializer.serialize_str(self.label()),
+        }
+    }
+}
+
+/// The `{ custom: ..., meter: N }` form
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CustomForm {
+    custom: String,
+    meter: u8,
+}
+
+impl<'de> Deserialize<'de> for VolumeLevel {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct VolumeVisitor;
+
+        impl<'de> Visitor<'de> for VolumeVisitor {
+            type Value = VolumeLevel;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a volume like \"loud\" or { custom: \"...\", meter: 1-5 }")
+            }
+
+            fn visit_str<E: de::Error>(self, text: &str) -> Result<Self::Value, E> {
+                Ok(VolumeLevel::parse(text))
+            }
+
+            fn visit_map<M: MapAccess<'de>>(self, map: M) -> Result<Self::Value, M::Error> {
+                let form = CustomForm::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                if !(1..=METER_STEPS).contains(&form.meter) {
+                    return Err(de::Error::custom(format!(
+                        "meter must be 1-{}, got {}",
+                        METER_STEPS, form.meter
+                    )));
+                }
+                Ok(VolumeLevel::Custom {
+                    text: form.custom,
+                    meter: Some(form.meter),
+                })
+            }
+        }
+
+        deserializer.deserialize_any(VolumeVisitor)
+    }
+}
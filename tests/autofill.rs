@@ -0,0 +1,98 @@
+//! autofill's Bandcamp page parsing against saved pages in tests/fixtures/bandcamp
+
+use digital_khole::bandcamp::{parse_page, tanka_file_name, tanka_yaml, PageInfo};
+use digital_khole::embed::SITE_PLAYER_COLORS;
+use digital_khole::form::PoemForm;
+use digital_khole::Tanka;
+
+fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/bandcamp/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+#[test]
+fn track_page() {
+    let info = parse_page(&fixture("track.html")).unwrap();
+
+    assert_eq!(
+        info,
+        PageInfo {
+            track: Some("Days Go By".to_string()),
+            artist: "Men I Trust".to_string(),
+            album: Some("Oncle Jazz".to_string()),
+            art_link: Some("https://f4.bcbits.com/img/a3970465110_10.jpg".to_string()),
+            album_id: Some(1115961286),
+            track_id: Some(3181857345),
+        }
+    );
+
+    // Same URL that was written by hand in content/mom.tanka.yml
+    assert_eq!(
+        info.embed().unwrap().iframe_url(&SITE_PLAYER_COLORS),
+        "https://bandcamp.com/EmbeddedPlayer/album=1115961286/track=3181857345/size=large/bgcol=000000/linkcol=4d96ff/tracklist=false/artwork=small/transparent=true/"
+    );
+}
+
+#[test]
+fn album_page() {
+    let info = parse_page(&fixture("album.html")).unwrap();
+
+    assert_eq!(info.track, None);
+    assert_eq!(info.album.as_deref(), Some("Rock & Roll \"Heaven\""));
+    assert_eq!(info.artist, "LapFox Trax");
+    // art_id is zero-padded to ten digits
+    assert_eq!(info.art_link.as_deref(), Some("https://f4.bcbits.com/img/a0023690698_10.jpg"));
+    assert_eq!(
+        info.embed().unwrap().iframe_url(&SITE_PLAYER_COLORS),
+        "https://bandcamp.com/EmbeddedPlayer/album=1107204502/size=large/bgcol=000000/linkcol=4d96ff/tracklist=false/artwork=small/transparent=true/"
+    );
+}
+
+#[test]
+fn page_without_player_data() {
+    let info = parse_page(&fixture("private.html")).unwrap();
+
+    assert_eq!(info.track.as_deref(), Some("puppy making song"));
+    assert_eq!(info.album.as_deref(), Some("BIG GAY SUMMER MIXTAPE"));
+    assert_eq!(info.art_link.as_deref(), Some("https://f4.bcbits.com/img/a1715592918_10.jpg"));
+    assert_eq!(info.embed(), None);
+}
+
+#[test]
+fn not_a_bandcamp_page() {
+    assert!(parse_page(&fixture("not-bandcamp.html")).is_err());
+}
+
+#[test]
+fn written_yaml_loads_as_a_tanka() {
+    let qr_link = "https://lapfox.bandcamp.com/album/renard-x-halv-y";
+    let info = parse_page(&fixture("album.html")).unwrap();
+    let yaml = tanka_yaml("ice: \"cold\"", PoemForm::Tanka, qr_link, &info);
+
+    let tanka: Tanka = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(tanka.top_flavor, "tanka // 57577 // [ ice: \"cold\" ] ");
+    assert_eq!(tanka.qr_link, qr_link);
+    assert_eq!(Some(tanka.art_link), info.art_link.clone());
+    assert_eq!(tanka.bandcamp_embed, info.embed());
+    assert_eq!(tanka.recommended_music_pairing.first().artist, "LapFox Trax");
+    assert_eq!(tanka.recommended_music_pairing.first().album, "Rock & Roll \"Heaven\"");
+
+    let private = tanka_yaml("dog", PoemForm::Tanka, qr_link, &parse_page(&fixture("private.html")).unwrap());
+    let tanka: Tanka = serde_yaml::from_str(&private).unwrap();
+    assert!(tanka.bandcamp_embed_isprivate);
+    assert_eq!(tanka.bandcamp_embed, None);
+
+    let haiku: Tanka = serde_yaml::from_str(&tanka_yaml("frog", PoemForm::Haiku, qr_link, &info)).unwrap();
+    assert_eq!(haiku.form, PoemForm::Haiku);
+    assert_eq!(haiku.top_flavor, "haiku // 575 // [ frog ] ");
+    assert_eq!(haiku.tanka.lines().len(), 3);
+}
+
+#[test]
+fn files_are_named_by_slug_and_stay_in_content() {
+    assert_eq!(tanka_file_name("Soul Check").unwrap(), "soul-check.tanka.yml");
+    assert_eq!(tanka_file_name("mom").unwrap(), "mom.tanka.yml");
+    for bad in ["../escape", "a/b", "..", "a\\b", "", "  ", ".hidden"] {
+        assert!(tanka_file_name(bad).is_err(), "accepted {:?}", bad);
+    }
+}
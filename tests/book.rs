@@ -0,0 +1,92 @@
+//! book.yml: chapters, the reading order and what the validator reports
+
+use digital_khole::all_tankas;
+use digital_khole::book::{book, neighbours, next_tanka, reading_order, Book, BookEntry};
+
+const BOOK: &str = "\
+chapters:
+  - title: one
+    intro: the start
+    entries: [a, {fragment: 1}]
+    sections:
+      - title: one.one
+        entries: [b]
+  - title: two
+    entries: [c, {fragment: '2'}, d]
+";
+
+fn tanka(slug: &str) -> BookEntry {
+    BookEntry::Tanka(slug.to_string())
+}
+
+fn strings(names: &[&str]) -> Vec<String> {
+    names.iter().map(|n| n.to_string()).collect()
+}
+
+#[test]
+fn entries_run_through_chapters_and_sections() {
+    let book = Book::parse(BOOK).unwrap();
+    assert_eq!(book.chapters[0].intro.as_deref(), Some("the start"));
+    assert_eq!(
+        book.entries().into_iter().map(BookEntry::href).collect::<Vec<_>>(),
+        ["/tanka/a", "/fragment/1", "/tanka/b", "/tanka/c", "/fragment/2", "/tanka/d"]
+    );
+    assert_eq!(book.fragments(), ["1", "2"]);
+
+    assert!(Book::parse("chapters: [{title: x, entries: [a], order: 1}]").is_err());
+    assert!(Book::parse("chapters: [{title: x, entries: [{chapter: a}]}]").is_err());
+
+    // build.rs compiles in content/<name>.fragmentedpoem, so names stay in content/
+    for bad in ["../Cargo", "drafts/1", "..", ".hidden", "a\\\\b", "''"] {
+        let yaml = format!("chapters: [{{title: x, entries: [{{fragment: {}}}]}}]", bad);
+        assert!(Book::parse(&yaml).is_err(), "accepted {:?}", bad);
+    }
+    assert!(Book::parse("chapters: [{title: x, entries: [{fragment: 1.from_print_version}]}]").is_ok());
+}
+
+#[test]
+fn prev_and_next_cross_chapters_and_sessions_skip_fragments() {
+    let order: Vec<BookEntry> = Book::parse(BOOK).unwrap().entries().into_iter().cloned().collect();
+
+    assert_eq!(neighbours(&order, &tanka("b")), (Some(BookEntry::Fragment("1".into())), Some(tanka("c"))));
+    assert_eq!(neighbours(&order, &tanka("a")).0, None);
+    assert_eq!(neighbours(&order, &tanka("d")).1, None);
+    assert_eq!(neighbours(&order, &tanka("nope")), (None, None));
+
+    assert_eq!(next_tanka(&order, &tanka("a")).as_deref(), Some("b"));
+    assert_eq!(next_tanka(&order, &tanka("c")).as_deref(), Some("d"));
+    assert_eq!(next_tanka(&order, &tanka("d")), None);
+}
+
+#[test]
+fn problems_name_missing_repeated_and_orphaned_entries() {
+    let book = Book::parse(BOOK).unwrap();
+    assert!(book.problems(&strings(&["a", "b", "c", "d"]), &strings(&["1", "2"])).is_empty());
+
+    let problems = book.problems(&strings(&["a", "b", "d", "e"]), &strings(&["1"]));
+    assert_eq!(
+        problems,
+        [
+            "c: no such tanka",
+            "fragment 2: no content/2.fragmentedpoem",
+            "e: not in any chapter",
+        ]
+    );
+
+    let twice = Book::parse("chapters: [{title: x, entries: [a, a]}]").unwrap();
+    assert_eq!(twice.problems(&strings(&["a"]), &[]), ["/tanka/a is listed more than once"]);
+}
+
+#[test]
+fn the_book_orders_every_tanka() {
+    let tankas = all_tankas();
+    let order = reading_order();
+    let listed: Vec<&BookEntry> = order.iter().filter(|e| matches!(e, BookEntry::Tanka(_))).collect();
+    assert_eq!(listed.len(), tankas.len());
+
+    // all_tankas() (and so the soundtrack and the printed book) follows book.yml
+    for (entry, tanka) in listed.into_iter().zip(&tankas) {
+        assert_eq!(*entry, BookEntry::Tanka(tanka.slug.clone()));
+    }
+    assert!(!book().chapters.is_empty());
+}
@@ -0,0 +1,79 @@
+//! Tanka files for the tests: a valid one to start from, with only the
+//! fields a test is about changed
+
+// Each test binary uses its own part of this
+#![allow(dead_code)]
+
+use digital_khole::{Tanka, TankaEntry};
+
+/// A `.tanka.yml` as top-level `key: value` lines, each value a YAML fragment
+/// (`{track: t, ...}` inline, or a block starting on the next line)
+#[derive(Debug, Clone)]
+pub struct TankaYaml {
+    fields: Vec<(&'static str, String)>,
+}
+
+impl Default for TankaYaml {
+    fn default() -> Self {
+        TankaYaml {
+            fields: vec![
+                ("top_flavor", "x".to_string()),
+                ("qr_link", "q".to_string()),
+                ("art_link", "a".to_string()),
+                ("recommended_music_pairing", "{track: t, artist: a, album: b}".to_string()),
+                ("tanka", "{1: a, 2: b, 3: c, 4: d, 5: e}".to_string()),
+                ("tankadesc", "d".to_string()),
+            ],
+        }
+    }
+}
+
+impl TankaYaml {
+    /// Set a field, keeping its place if it's already there
+    pub fn with(mut self, key: &'static str, value: impl Into<String>) -> Self {
+        let value = value.into();
+        match self.fields.iter_mut().find(|(k, _)| *k == key) {
+            Some(field) => field.1 = value,
+            None => self.fields.push((key, value)),
+        }
+        self
+    }
+
+    /// Leave a field out
+    pub fn without(mut self, key: &str) -> Self {
+        self.fields.retain(|(k, _)| *k != key);
+        self
+    }
+
+    /// The lines under `poem:` instead of the five under `tanka:`
+    pub fn poem(self, lines: impl Into<String>) -> Self {
+        self.without("tanka").with("poem", lines)
+    }
+
+    /// A setlist or single pairing written out as a block
+    pub fn pairing(self, block: impl Into<String>) -> Self {
+        self.with("recommended_music_pairing", format!("\n{}", block.into()))
+    }
+
+    pub fn yaml(&self) -> String {
+        self.fields.iter().map(|(key, value)| format!("{}: {}\n", key, value)).collect()
+    }
+
+    pub fn parse(&self) -> Result<Tanka, String> {
+        serde_yaml::from_str(&self.yaml()).map_err(|e| e.to_string())
+    }
+
+    pub fn tanka(&self) -> Tanka {
+        self.parse().unwrap_or_else(|e| panic!("{}\n{}", e, self.yaml()))
+    }
+
+    /// The tanka as the book lists it, from `<slug>.tanka.yml`
+    pub fn entry(&self, slug: &str) -> TankaEntry {
+        TankaEntry {
+            slug: slug.to_string(),
+            filename: format!("{}.tanka.yml", slug),
+            tanka: self.tanka(),
+            translations: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! bandcamp_embed in both its legacy URL and structured YAML forms
+
+mod common;
+
+use common::TankaYaml;
+use digital_khole::embed::{BandcampEmbed, PlayerArtwork, PlayerColors, PlayerSize, SITE_PLAYER_COLORS};
+
+fn parse(yaml: &str) -> Result<BandcampEmbed, String> {
+    serde_yaml::from_str(yaml).map_err(|e| e.to_string())
+}
+
+#[test]
+fn legacy_url_round_trips_with_site_colors() {
+    let url = "https://bandcamp.com/EmbeddedPlayer/album=1115961286/track=3181857345/size=large/bgcol=000000/linkcol=4d96ff/tracklist=false/artwork=small/transparent=true/";
+    let embed = parse(&format!("\"{}\"", url)).unwrap();
+
+    assert_eq!(embed.album, Some(1115961286));
+    assert_eq!(embed.track, Some(3181857345));
+    assert_eq!(embed.bgcol, None);
+    assert_eq!(embed.iframe_url(&SITE_PLAYER_COLORS), url);
+}
+
+#[test]
+fn legacy_url_options_in_any_order() {
+    let embed = parse("https://bandcamp.com/EmbeddedPlayer/album=2744571760/size=large/bgcol=000000/linkcol=4d96ff/tracklist=false/track=1864262977/artwork=small/transparent=true/").unwrap();
+    assert_eq!(embed.album, Some(2744571760));
+    assert_eq!(embed.track, Some(1864262977));
+}
+
+#[test]
+fn structured_form_follows_theme_unless_pinned() {
+    let embed = parse("album: 1349219244\nsize: small\nartwork: none\n").unwrap();
+    assert_eq!(embed.size, PlayerSize::Small);
+    assert_eq!(embed.artwork, PlayerArtwork::None);
+
+    let light = PlayerColors { background: "ffffff", link: "0000ee" };
+    assert_eq!(
+        embed.iframe_url(&light),
+        "https://bandcamp.com/EmbeddedPlayer/album=1349219244/size=small/bgcol=ffffff/linkcol=0000ee/tracklist=false/artwork=none/transparent=true/"
+    );
+
+    let pinned = parse("track: 42\nlinkcol: ff6b6b\n").unwrap();
+    assert!(pinned.iframe_url(&light).contains("/bgcol=ffffff/linkcol=ff6b6b/"));
+}
+
+#[test]
+fn malformed_embeds_are_rejected() {
+    for bad in [
+        "https://example.com/EmbeddedPlayer/album=1/",
+        "https://bandcamp.com/EmbeddedPlayer/album=XXXXX/size=small/",
+        "https://bandcamp.com/EmbeddedPlayer/size=large/",
+        "https://bandcamp.com/EmbeddedPlayer/album=1/size=huge/",
+        "https://bandcamp.com/EmbeddedPlayer/album=1/autoplay=true/",
+        "album: 1\nbgcol: \"#000000\"\n",
+        "album: 1\ncolour: red\n",
+        "size: large\n",
+    ] {
+        assert!(parse(bad).is_err(), "accepted {:?}", bad);
+    }
+}
+
+fn tanka(pairing_extra: &str, tanka: TankaYaml) -> digital_khole::Tanka {
+    tanka
+        .with("qr_link", "https://example.bandcamp.com/track/x")
+        .with("art_link", "null")
+        .pairing(format!("  track: t\n  artist: a\n  album: b\n  volume_level: loud\n{}", pairing_extra))
+        .tanka()
+}
+
+#[test]
+fn best_player_falls_back_in_order() {
+    use digital_khole::embed::{best_player, pairing_links, Player};
+
+    let all = "  soundcloud: https://soundcloud.com/agcook/7g\n  youtube: https://youtu.be/dQw4w9WgXcQ\n  audio: /audio/7g.opus\n";
+
+    let t = tanka(all, TankaYaml::default().with("bandcamp_embed", "{album: 1}"));
+    assert!(matches!(best_player(&t.pairings()[0], &SITE_PLAYER_COLORS), Some(Player::Bandcamp(_))));
+
+    let private = TankaYaml::default().with("bandcamp_embed", "{album: 1}").with("bandcamp_embed_isprivate", "true");
+    let t = tanka(all, private);
+    assert_eq!(
+        best_player(&t.pairings()[0], &SITE_PLAYER_COLORS),
+        Some(Player::SoundCloud(
+            "https://w.soundcloud.com/player/?url=https%3A%2F%2Fsoundcloud.com%2Fagcook%2F7g&color=%234d96ff&auto_play=false&visual=false".to_string()
+        ))
+    );
+
+    let t = tanka("  youtube: https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=42\n  audio: /audio/7g.opus\n", TankaYaml::default());
+    assert_eq!(
+        best_player(&t.pairings()[0], &SITE_PLAYER_COLORS),
+        Some(Player::YouTube("https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ".to_string()))
+    );
+
+    let t = tanka("  audio: /audio/7g.opus\n", TankaYaml::default());
+    assert_eq!(best_player(&t.pairings()[0], &SITE_PLAYER_COLORS), Some(Player::Audio("/audio/7g.opus".to_string())));
+
+    // Nothing embeddable: just the link list, with qr_link standing in for bandcamp
+    let t = tanka("  text: on Spotify as 7G by A. G. Cook\n", TankaYaml::default());
+    assert_eq!(best_player(&t.pairings()[0], &SITE_PLAYER_COLORS), None);
+    let links = pairing_links(&t.pairings()[0]);
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].label, "bandcamp");
+}
+
+#[test]
+fn bad_service_links_are_reported() {
+    use digital_khole::embed::link_problems;
+
+    let t = tanka(
+        "  soundcloud: https://on.soundcloud.com/x\n  youtube: https://www.youtube.com/@agcook\n  audio: /audio/7g.txt\n",
+        TankaYaml::default(),
+    );
+    let fields: Vec<_> = link_problems(&t.pairings()[0]).into_iter().map(|(field, _)| field).collect();
+    assert_eq!(fields, ["soundcloud", "youtube", "audio"]);
+}
+
+#[test]
+fn session_autoplay_and_end_messages() {
+    use digital_khole::embed::{read_player_message, Player, PlayerMessage, SOUNDCLOUD_ORIGIN, YOUTUBE_ORIGIN};
+
+    let youtube = Player::YouTube("https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ".to_string()).autoplay();
+    assert_eq!(
+        youtube,
+        Player::YouTube("https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ?autoplay=1&enablejsapi=1".to_string())
+    );
+    let soundcloud = Player::SoundCloud("https://w.soundcloud.com/player/?url=x&auto_play=false".to_string()).autoplay();
+    assert_eq!(soundcloud, Player::SoundCloud("https://w.soundcloud.com/player/?url=x&auto_play=true".to_string()));
+    assert!(!Player::Bandcamp(String::new()).reports_end());
+
+    assert_eq!(read_player_message(SOUNDCLOUD_ORIGIN, r#"{"method":"ready","value":null}"#), Some(PlayerMessage::Ready));
+    assert_eq!(read_player_message(SOUNDCLOUD_ORIGIN, r#"{"method":"finish","value":{}}"#), Some(PlayerMessage::Finished));
+    assert_eq!(read_player_message(YOUTUBE_ORIGIN, r#"{"event":"onStateChange","info":0}"#), Some(PlayerMessage::Finished));
+    assert_eq!(
+        read_player_message(YOUTUBE_ORIGIN, r#"{"event":"infoDelivery","info":{"playerState":0,"currentTime":212}}"#),
+        Some(PlayerMessage::Finished)
+    );
+    // Paused, and lookalike messages from anywhere else
+    assert_eq!(read_player_message(YOUTUBE_ORIGIN, r#"{"event":"onStateChange","info":2}"#), None);
+    assert_eq!(read_player_message("https://evil.example", r#"{"method":"finish"}"#), None);
+}
@@ -0,0 +1,125 @@
+//! fetch-assets against a local HTTP stand-in for bcbits.com
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::thread;
+
+use digital_khole::art::ArtFormat;
+use digital_khole::art_fetch::{download, is_complete, load_manifest, mirror, save_manifest};
+
+/// Serve `body` as image/jpeg at `path` and 404 everything else, returning the base URL
+fn stand_in(path: &'static str, body: Vec<u8>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+
+            // drain headers
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+
+            let requested = request_line.split_whitespace().nth(1).unwrap_or_default();
+            let mut stream = stream;
+            if requested == path {
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                )
+                .unwrap();
+                stream.write_all(&body).unwrap();
+            } else {
+                write!(stream, "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").unwrap();
+            }
+        }
+    });
+
+    format!("http://{}", addr)
+}
+
+/// A 400x300 gradient, standing in for album art
+fn sample_jpeg() -> Vec<u8> {
+    let image = image::RgbImage::from_fn(400, 300, |x, y| image::Rgb([(x / 2) as u8, (y / 2) as u8, 128]));
+    let mut bytes = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new(&mut bytes)
+        .encode_image(&image)
+        .unwrap();
+    bytes
+}
+
+fn temp_store(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("digital-khole-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn downloads_and_mirrors_art() {
+    let jpeg = sample_jpeg();
+    let base = stand_in("/img/a123_10.jpg", jpeg.clone());
+    let store = temp_store("mirror");
+
+    let bytes = download(&format!("{}/img/a123_10.jpg", base)).unwrap();
+    assert_eq!(bytes, jpeg);
+
+    let art = mirror(&bytes, &store).unwrap();
+    assert_eq!(art.hash.len(), 64);
+    assert_eq!(art.original_ext, "jpg");
+    assert_eq!((art.width, art.height), (400, 300));
+    assert_eq!(art.widths, vec![180, 360], "never upscales past the original");
+    assert!(!art.blurhash.is_empty());
+    assert!(is_complete(&art, &store));
+
+    let small = image::load_from_memory(&std::fs::read(store.join(art.variant_file(180, ArtFormat::Jpeg))).unwrap()).unwrap();
+    assert_eq!((small.width(), small.height()), (180, 135));
+
+    assert_eq!(
+        art.srcset(ArtFormat::Avif),
+        format!("/art/w180-{0}.avif 180w, /art/w360-{0}.avif 360w", art.hash)
+    );
+    assert_eq!(art.print_url(), format!("/art/w360-{}.jpg", art.hash), "print takes the largest variant");
+
+    std::fs::remove_dir_all(&store).unwrap();
+}
+
+#[test]
+fn identical_bytes_share_a_store_entry() {
+    let jpeg = sample_jpeg();
+    let store = temp_store("dedupe");
+
+    let first = mirror(&jpeg, &store).unwrap();
+    let second = mirror(&jpeg, &store).unwrap();
+    assert_eq!(first.hash, second.hash);
+
+    std::fs::remove_dir_all(&store).unwrap();
+}
+
+#[test]
+fn missing_art_is_an_error() {
+    let base = stand_in("/img/exists.jpg", sample_jpeg());
+    assert!(download(&format!("{}/img/missing.jpg", base)).is_err());
+}
+
+#[test]
+fn manifest_round_trips() {
+    let store = temp_store("manifest");
+    let art = mirror(&sample_jpeg(), &store).unwrap();
+
+    let mut manifest = load_manifest(&store);
+    assert!(manifest.images.is_empty());
+
+    manifest.images.insert("https://f4.bcbits.com/img/a123_10.jpg".to_string(), art.clone());
+    save_manifest(&store, &manifest).unwrap();
+
+    let loaded = load_manifest(&store);
+    assert_eq!(loaded.get("https://f4.bcbits.com/img/a123_10.jpg"), Some(&art));
+
+    std::fs::remove_dir_all(&store).unwrap();
+}
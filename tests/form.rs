@@ -0,0 +1,62 @@
+//! Poem forms: reading `form:` and the lines, and how each form lays them out
+
+mod common;
+
+use common::TankaYaml;
+use digital_khole::form::PoemForm;
+use digital_khole::{Poem, Tanka};
+
+fn poem(form: &str, lines: &str) -> Tanka {
+    TankaYaml::default().with("form", form).poem(lines).tanka()
+}
+
+#[test]
+fn existing_files_are_tankas() {
+    let tanka = TankaYaml::default().tanka();
+    assert_eq!(tanka.form, PoemForm::Tanka);
+    assert_eq!(tanka.tanka.as_vec(), ["a", "b", "c", "d", "e"]);
+
+    let haiku = poem("haiku", "{3: c, 1: a, 2: b}");
+    assert_eq!(haiku.form, PoemForm::Haiku);
+    assert_eq!(haiku.tanka.as_vec(), ["a", "b", "c"]);
+
+    let free = poem("free-verse", "[one, two, \"\", three]");
+    assert_eq!(free.form, PoemForm::FreeVerse);
+    assert_eq!(free.tanka.lines().len(), 4);
+
+    // Serializing keeps the numbered lines
+    let yaml = serde_yaml::to_string(&haiku).unwrap();
+    assert!(yaml.contains("form: haiku") && yaml.contains("'3': c"), "{}", yaml);
+}
+
+#[test]
+fn lines_are_numbered_from_one_without_gaps() {
+    for bad in ["1: a\n3: c\n", "0: a\n1: b\n", "one: a\n", "[]"] {
+        assert!(serde_yaml::from_str::<Poem>(bad).is_err(), "accepted {:?}", bad);
+    }
+    let limerick = TankaYaml::default().with("form", "limerick").poem("[a]");
+    assert!(limerick.parse().is_err());
+    assert!(limerick.with("form", "haiku").parse().is_ok());
+}
+
+#[test]
+fn forms_lay_out_their_stanzas() {
+    let sedoka = poem("sedoka", "[a, b, c, d, e, f]");
+    let stanzas: Vec<usize> = sedoka.tanka.stanzas(PoemForm::Sedoka).iter().map(|s| s.len()).collect();
+    assert_eq!(stanzas, [3, 3]);
+
+    let free = poem("free-verse", "[a, b, \"\", c, \"  \", \"\", d]");
+    let stanzas: Vec<usize> = free.tanka.stanzas(PoemForm::FreeVerse).iter().map(|s| s.len()).collect();
+    assert_eq!(stanzas, [2, 1, 1]);
+
+    // The validator reports a short tanka; the page still shows what's there
+    let short = TankaYaml::default().with("tanka", "[a, b, c]").tanka();
+    assert_eq!(short.tanka.stanzas(PoemForm::Tanka).len(), 1);
+
+    assert_eq!(PoemForm::Tanka.pattern().as_deref(), Some("57577"));
+    assert_eq!(PoemForm::Cinquain.pattern().as_deref(), Some("24682"));
+    assert_eq!(PoemForm::FreeVerse.pattern(), None);
+    for form in PoemForm::ALL {
+        assert_eq!(PoemForm::from_name(form.name()), Some(form));
+    }
+}
@@ -0,0 +1,68 @@
+//! Locale bundles, negotiation and translated tanka commentary
+
+mod common;
+
+use common::TankaYaml;
+use digital_khole::i18n::{message_ids, Locale, MESSAGES};
+use digital_khole::theme::Theme;
+use digital_khole::{load_translation, translation_of};
+
+#[test]
+fn every_locale_has_every_message_the_ui_uses() {
+    for locale in Locale::ALL {
+        let ids = message_ids(locale.source()).unwrap_or_else(|e| panic!("{}: {}", locale, e));
+        let missing: Vec<_> = MESSAGES.iter().filter(|id| !ids.iter().any(|i| i == *id)).collect();
+        assert!(missing.is_empty(), "{} is missing {:?}", locale, missing);
+    }
+
+    // Nothing in English that the UI never asks for
+    let english = message_ids(Locale::En.source()).unwrap();
+    let unused: Vec<_> = english.iter().filter(|id| !MESSAGES.contains(&id.as_str())).collect();
+    assert!(unused.is_empty(), "en.ftl has {:?}, which i18n::MESSAGES doesn't list", unused);
+
+    // The theme switcher asks for `theme-<name>`
+    for theme in Theme::ALL {
+        let id = format!("theme-{}", theme.name());
+        assert!(MESSAGES.contains(&id.as_str()), "{} isn't in i18n::MESSAGES", id);
+    }
+}
+
+#[test]
+fn negotiates_from_browser_languages() {
+    assert_eq!(Locale::negotiate(&["ja-JP", "en-US"]), Locale::Ja);
+    assert_eq!(Locale::negotiate(&["en-GB", "ja"]), Locale::En);
+    assert_eq!(Locale::negotiate(&["fr-FR", "ja"]), Locale::Ja);
+    assert_eq!(Locale::negotiate(&["de-DE", "not a tag!"]), Locale::En);
+    assert_eq!(Locale::negotiate::<&str>(&[]), Locale::En);
+}
+
+#[test]
+fn messages_fill_arguments_and_fall_back() {
+    assert_eq!(
+        Locale::En.tr_with("session-up-next", &[("secs", 5.into())]),
+        "listening session: up next in 5s"
+    );
+    assert_eq!(Locale::Ja.tr("nav-next"), "次へ >");
+    assert_eq!(Locale::En.tr("cw-label"), "content warning: ");
+    assert_eq!(Locale::Ja.tr("no-such-message"), "no-such-message");
+}
+
+#[test]
+fn translations_override_commentary_per_language() {
+    assert_eq!(translation_of("mom.tanka.ja.yml"), Some(("mom.tanka.yml".to_string(), "ja")));
+    assert_eq!(translation_of("mom.tanka.yml"), None);
+    assert_eq!(translation_of("notes.yml"), None);
+
+    let mut entry = TankaYaml::default()
+        .with("tankadesc", "about it")
+        .with("tastingnotes", "the song")
+        .entry("mom");
+    entry.translations = vec![("ja".to_string(), load_translation("tankadesc: これについて\n").unwrap())];
+
+    assert_eq!(entry.tankadesc("ja"), "これについて");
+    assert_eq!(entry.tastingnotes("ja"), Some("the song"));
+    assert_eq!(entry.tankadesc("en"), "about it");
+
+    // Only commentary is translated, so anything else is a typo
+    assert!(load_translation("tanka: {1: a}\n").is_none());
+}
@@ -0,0 +1,47 @@
+//! Japanese verses: ruby markup, mora counts and the verse forms in YAML
+
+use digital_khole::japanese::{count_morae, parse_ruby, plain_text, RubySegment};
+use digital_khole::Poem;
+
+fn morae(ja: &str) -> Result<usize, String> {
+    count_morae(&parse_ruby(ja)?)
+}
+
+#[test]
+fn ruby_markup_splits_into_segments() {
+    let segments = parse_ruby("{物|もの}の{哀|あわ}れ").unwrap();
+    assert_eq!(
+        segments[0],
+        RubySegment::Ruby { base: "物".to_string(), reading: "もの".to_string() }
+    );
+    assert_eq!(plain_text(&segments), "物の哀れ");
+
+    for bad in ["{物もの}の", "{物|もの", "もの}", "{|もの}"] {
+        assert!(parse_ruby(bad).is_err(), "accepted {:?}", bad);
+    }
+}
+
+#[test]
+fn morae_count_kana_and_readings() {
+    // ふるいけや / かわずとびこむ / みずのおと
+    assert_eq!(morae("{古池|ふるいけ}や"), Ok(5));
+    assert_eq!(morae("{蛙|かわず}{飛|と}び{込|こ}む"), Ok(7));
+    // small ゃ joins its kana, っ and ー count on their own
+    assert_eq!(morae("きゃっきゃー"), Ok(4));
+    assert_eq!(morae("もの、 あわれ。"), Ok(5));
+    assert!(morae("物の哀れ").unwrap_err().contains("物"));
+}
+
+#[test]
+fn verses_are_strings_or_maps() {
+    let verses: Poem = serde_yaml::from_str(
+        "1: hello dragon friend.\n2: 1999\n3: null\n4: a leaf\n5: {text: \"that's mono no aware\", ja: \"{物|もの}の{哀|あわ}れ\"}\n",
+    )
+    .unwrap();
+
+    assert_eq!(verses.as_vec(), ["hello dragon friend.", "1999", "null", "a leaf", "that's mono no aware"]);
+    assert!(verses.has_japanese());
+    assert_eq!(verses.lines()[4].ja.as_deref(), Some("{物|もの}の{哀|あわ}れ"));
+
+    assert!(serde_yaml::from_str::<Poem>("1: a\n2: b\n3: c\n4: d\n5: {text: e, jp: f}\n").is_err());
+}
@@ -0,0 +1,73 @@
+//! recommended_music_pairing as a single pairing or an ordered setlist
+
+mod common;
+
+use common::TankaYaml;
+use digital_khole::Tanka;
+
+fn tanka(pairing: &str) -> Result<Tanka, String> {
+    TankaYaml::default()
+        .with("qr_link", "https://example.bandcamp.com/track/first")
+        .with("art_link", "https://f4.bcbits.com/img/a0000000001_10.jpg")
+        .with("bandcamp_embed", "{album: 1, track: 2}")
+        .pairing(pairing)
+        .parse()
+}
+
+#[test]
+fn single_pairing_still_loads() {
+    let t = tanka("  track: t\n  artist: a\n  album: b\n  volume_level: loud\n").unwrap();
+
+    assert_eq!(t.recommended_music_pairing.len(), 1);
+    let pairings = t.pairings();
+    assert_eq!(pairings[0].track, "t");
+    assert_eq!(pairings[0].qr_link.as_deref(), Some("https://example.bandcamp.com/track/first"));
+    assert_eq!(pairings[0].bandcamp_embed.as_ref().and_then(|e| e.track), Some(2));
+}
+
+#[test]
+fn setlist_entries_keep_their_own_links() {
+    let t = tanka(
+        "  - {track: one, artist: a, album: b, volume_level: quiet, note: first}
+  - track: two
+    artist: a
+    album: c
+    volume_level: loud
+    qr_link: https://example.bandcamp.com/track/second
+    bandcamp_embed: {album: 3, track: 4}
+  - {track: three, artist: a, album: c, volume_level: off, youtube: https://youtu.be/dQw4w9WgXcQ}
+",
+    )
+    .unwrap();
+
+    let pairings = t.pairings();
+    assert_eq!(pairings.len(), 3);
+    assert_eq!(pairings[0].note.as_deref(), Some("first"));
+    assert_eq!(pairings[0].bandcamp_embed.as_ref().and_then(|e| e.album), Some(1));
+    assert_eq!(pairings[1].qr_link.as_deref(), Some("https://example.bandcamp.com/track/second"));
+    assert_eq!(pairings[1].bandcamp_embed.as_ref().and_then(|e| e.album), Some(3));
+
+    // The tanka's own links and player belong to the first entry only
+    assert_eq!(pairings[0].art_link.as_deref(), Some("https://f4.bcbits.com/img/a0000000001_10.jpg"));
+    assert_eq!(pairings[1].art_link, None);
+    assert_eq!(pairings[2].bandcamp_embed, None);
+    assert_eq!(pairings[2].qr_link, None);
+    assert_eq!(pairings[2].art_link, None);
+}
+
+#[test]
+fn empty_setlist_is_rejected() {
+    assert!(tanka("  []\n").is_err());
+}
+
+#[test]
+fn every_content_file_still_loads() {
+    let content = format!("{}/content", env!("CARGO_MANIFEST_DIR"));
+    for entry in std::fs::read_dir(content).unwrap().flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.ends_with(".tanka.yml") && !name.contains("template") {
+            let yaml = std::fs::read_to_string(entry.path()).unwrap();
+            serde_yaml::from_str::<Tanka>(&yaml).unwrap_or_else(|e| panic!("{}: {}", name, e));
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! /today, /random and the RSS feed that leads with the day's pick
+
+use digital_khole::all_tankas;
+use digital_khole::feed::rss;
+use digital_khole::pick::{civil, daily, daily_index, iso_date, random_slug, remember, RECENT_LIMIT};
+
+fn slugs(names: &[&str]) -> Vec<String> {
+    names.iter().map(|n| n.to_string()).collect()
+}
+
+#[test]
+fn day_numbers_are_calendar_dates() {
+    assert_eq!(civil(0), (1970, 1, 1));
+    assert_eq!(iso_date(20_744), "2026-10-18");
+    assert_eq!(iso_date(11_016), "2000-02-29");
+    assert_eq!(iso_date(-1), "1969-12-31");
+}
+
+#[test]
+fn the_daily_pick_is_the_same_all_day_and_varies_across_days() {
+    assert_eq!(daily_index(20_744, 0), None);
+    assert_eq!(daily_index(20_744, 14), daily_index(20_744, 14));
+
+    let picks: Vec<usize> = (0..60).map(|day| daily_index(20_744 + day, 14).unwrap()).collect();
+    assert!(picks.iter().all(|&i| i < 14));
+    let mut distinct = picks.clone();
+    distinct.sort();
+    distinct.dedup();
+    assert!(distinct.len() > 7, "two months of picks only covered {:?}", distinct);
+
+    let tankas = all_tankas();
+    assert_eq!(daily(&tankas, 20_744).unwrap().slug, tankas[daily_index(20_744, tankas.len()).unwrap()].slug);
+}
+
+#[test]
+fn random_avoids_what_was_just_read() {
+    let book = slugs(&["a", "b", "c", "d"]);
+    for roll in [0.0, 0.3, 0.6, 0.999] {
+        assert_eq!(random_slug(&book, &slugs(&["a", "b", "c"]), roll).as_deref(), Some("d"));
+    }
+    // Everything was read lately: the oldest comes back first
+    assert_eq!(random_slug(&book, &slugs(&["a", "b", "c", "d"]), 0.5).as_deref(), Some("a"));
+    assert_eq!(random_slug(&slugs(&["only"]), &slugs(&["only"]), 0.5).as_deref(), Some("only"));
+    assert_eq!(random_slug(&[], &[], 0.5), None);
+
+    let mut recent = slugs(&["a", "b"]);
+    remember(&mut recent, "a");
+    assert_eq!(recent, ["b", "a"]);
+    for slug in ["c", "d", "e", "f", "g"] {
+        remember(&mut recent, slug);
+    }
+    assert_eq!(recent.len(), RECENT_LIMIT);
+    assert_eq!(recent.last().map(String::as_str), Some("g"));
+}
+
+#[test]
+fn the_feed_leads_with_the_days_pick() {
+    let tankas = all_tankas();
+    let feed = rss(&tankas, "https://example.com/", 20_744);
+    let today = daily(&tankas, 20_744).unwrap();
+
+    assert!(feed.starts_with("<?xml"));
+    assert!(feed.contains("<lastBuildDate>Sun, 18 Oct 2026 00:00:00 GMT</lastBuildDate>"), "{}", feed);
+    let first_item = feed.split("<item>").nth(1).unwrap();
+    assert!(first_item.contains(&format!("<link>https://example.com/tanka/{}</link>", today.slug)));
+    assert!(first_item.contains("<guid isPermaLink=\"false\">https://example.com/today/2026-10-18</guid>"));
+    assert_eq!(feed.matches("<item>").count(), tankas.len() + 1);
+    assert!(!feed.contains(" & "), "text is escaped");
+}
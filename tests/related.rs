@@ -0,0 +1,62 @@
+//! "See also": what build.rs compares tankas on, and how it ranks them
+
+use digital_khole::all_tankas;
+use digital_khole::related::{related, similarity, words, Features, RELATED_COUNT};
+
+fn features(tags: &[&str], artist: &str, text: &str) -> Features {
+    Features {
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+        artists: vec![artist.to_string()],
+        albums: vec![format!("{} album", artist)],
+        text: text.to_string(),
+    }
+}
+
+#[test]
+fn features_come_from_verses_commentary_and_pairings() {
+    let tanka = Features::from_yaml(
+        "form: haiku\npoem: [one line, {text: second line, ja: 二}, third]\n\
+         recommended_music_pairing:\n  - {track: t, artist: 4lung, album: A}\n  - {track: u, artist: Other, album: B}\n\
+         tankadesc: about it\ntags: [grief, internet]\n",
+    );
+    assert_eq!(tanka.tags, ["grief", "internet"]);
+    assert_eq!(tanka.artists, ["4lung", "other"]);
+    assert_eq!(tanka.albums, ["a", "b"]);
+    assert_eq!(tanka.text, "one line\nsecond line\nthird\nabout it");
+
+    let numbered = Features::from_yaml("tanka: {1: a, 2: b}\nrecommended_music_pairing: {artist: X}\n");
+    assert_eq!(numbered.text, "a\nb");
+    assert_eq!(numbered.artists, ["x"]);
+
+    assert!(Features::from_yaml("not: [valid").tags.is_empty());
+    assert_eq!(words("The dogs, and THE dog's bark!"), ["dogs", "dog", "bark"]);
+}
+
+#[test]
+fn shared_tags_artists_and_words_rank_higher() {
+    let tankas = [
+        features(&["grief"], "a", "mother hugs rugs"),
+        features(&["grief"], "b", "keyboard ping"),
+        features(&[], "a", "beach waves"),
+        features(&[], "c", "mother rugs beach"),
+        features(&[], "d", "nothing alike here"),
+    ];
+    let scores = similarity(&tankas);
+    assert_eq!(scores[0][0], 0.0);
+    assert_eq!(scores[0][1], scores[1][0]);
+    assert!(scores[0][2] > scores[0][1], "a shared artist and album outweigh one tag");
+    assert!(scores[0][3] > 0.0 && scores[0][4] == 0.0);
+
+    let ranked = related(&tankas, 3);
+    assert_eq!(ranked[0], [2, 3, 1]);
+    assert!(ranked[4].is_empty(), "nothing in common, nothing listed");
+}
+
+#[test]
+fn every_page_gets_its_see_also_from_the_build() {
+    for entry in all_tankas() {
+        let related = entry.related();
+        assert!(!related.is_empty() && related.len() <= RELATED_COUNT, "{}", entry.slug);
+        assert!(related.iter().all(|other| other.slug != entry.slug), "{} lists itself", entry.slug);
+    }
+}
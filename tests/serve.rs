@@ -0,0 +1,160 @@
+//! What `serve` sends: cache headers, precompressed siblings, the CSP's
+//! inline script hashes, and real 404s, checked against the built binary
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+use digital_khole::all_tankas;
+use digital_khole::csp::{inline_script_hashes, ContentSources};
+use digital_khole::dist::{cache_control, is_hashed, precompress, CACHE_IMMUTABLE, CACHE_REVALIDATE};
+use digital_khole::ssr::{prepare_shell, HYDRATION_GLOBALS};
+
+const INDEX_HTML: &str = "<html><head><title>x</title>\
+    <script type=\"module\">import init from '/digital-khole-3f2a1b9c8d7e6f50.js'; init();</script>\
+    <script src=\"/other.js\"></script></head><body></body></html>";
+
+fn temp_dist(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("digital-khole-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("assets")).unwrap();
+    dir
+}
+
+fn sha256_source(script: &str) -> String {
+    format!("'sha256-{}'", base64::engine::general_purpose::STANDARD.encode(Sha256::digest(script.as_bytes())))
+}
+
+#[test]
+fn only_content_hashed_names_are_cached_forever() {
+    assert!(is_hashed("digital-khole-3f2a1b9c8d7e6f50_bg.wasm"));
+    assert!(is_hashed("style-3f2a1b9c8d7e6f50.css"));
+    for name in ["index.html", "style.css", "favicon.ico", "soundtrack.m3u8", "style-3f2a1b.css", "style-xyzxyzxyzxyzxyzx.css"] {
+        assert!(!is_hashed(name), "{}", name);
+    }
+
+    assert_eq!(cache_control("style-3f2a1b9c8d7e6f50.css", true), CACHE_IMMUTABLE);
+    assert_eq!(cache_control("style-3f2a1b9c8d7e6f50.css", false), CACHE_REVALIDATE);
+    assert_eq!(cache_control("index.html", true), CACHE_REVALIDATE);
+}
+
+#[test]
+fn precompresses_text_once_and_leaves_images_alone() {
+    let dir = temp_dist("precompress");
+    std::fs::write(dir.join("index.html"), INDEX_HTML.repeat(20)).unwrap();
+    std::fs::write(dir.join("assets/data.json"), "{}").unwrap();
+    std::fs::write(dir.join("assets/art.png"), [0u8; 64]).unwrap();
+
+    assert_eq!(precompress(&dir).unwrap(), 2);
+    for file in ["index.html", "assets/data.json"] {
+        assert!(dir.join(format!("{}.gz", file)).exists() && dir.join(format!("{}.br", file)).exists(), "{}", file);
+    }
+    assert!(!dir.join("assets/art.png.gz").exists());
+
+    // Siblings newer than their source are kept
+    assert_eq!(precompress(&dir).unwrap(), 0);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn the_csp_allows_exactly_the_inline_scripts_of_the_shell() {
+    let hashes = inline_script_hashes(INDEX_HTML);
+    assert_eq!(hashes, [sha256_source("import init from '/digital-khole-3f2a1b9c8d7e6f50.js'; init();")]);
+
+    // The SSR shell adds the hydration globals, which need a hash of their own
+    let shell = prepare_shell(INDEX_HTML);
+    assert!(!shell.contains("<title>"));
+    let globals = HYDRATION_GLOBALS.trim_start_matches("<script>").trim_end_matches("</script>");
+    let csp = ContentSources::default().content_security_policy(&inline_script_hashes(&shell));
+    assert!(csp.contains(&sha256_source(globals)), "{}", csp);
+    assert!(csp.contains(&hashes[0]), "{}", csp);
+
+    assert!(csp.contains("default-src 'none'"), "{}", csp);
+    assert!(csp.contains("frame-src 'none'"), "{}", csp);
+    assert!(csp.contains("frame-ancestors 'none'"), "{}", csp);
+
+    let sources = ContentSources::from_tankas(all_tankas().iter().map(|t| &t.tanka));
+    let csp = sources.content_security_policy(&[]);
+    for origin in &sources.img_origins {
+        assert!(csp.contains(origin.as_str()), "{} missing from {}", origin, csp);
+    }
+}
+
+/// `serve` on a free port over `dir`, killed when dropped
+struct Server(Child, u16);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn start(dir: &std::path::Path, base_url: Option<&str>) -> Server {
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let child = Command::new(env!("CARGO_BIN_EXE_serve"))
+        .args(["--port", &port.to_string(), "--dist"])
+        .arg(dir)
+        .args(base_url.iter().flat_map(|url| ["--base-url", url]))
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    for _ in 0..100 {
+        if TcpStream::connect(("127.0.0.1", port)).is_ok() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    Server(child, port)
+}
+
+/// The status line and headers of a GET, lowercased
+fn head(server: &Server, path: &str) -> String {
+    let mut stream = TcpStream::connect(("127.0.0.1", server.1)).unwrap();
+    write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response.split("\r\n\r\n").next().unwrap_or_default().to_lowercase()
+}
+
+#[test]
+fn serves_pages_assets_and_real_404s() {
+    let dir = temp_dist("serve");
+    std::fs::write(dir.join("index.html"), INDEX_HTML).unwrap();
+    std::fs::write(dir.join("style-3f2a1b9c8d7e6f50.css"), "body {}").unwrap();
+    let server = start(&dir, Some("https://khole.example"));
+
+    let slug = &all_tankas()[0].slug;
+    let page = head(&server, &format!("/tanka/{}", slug));
+    assert!(page.starts_with("http/1.1 200"), "{}", page);
+    assert!(page.contains("cache-control: no-cache"), "{}", page);
+    assert!(page.contains("content-security-policy: default-src 'none'"), "{}", page);
+    assert!(page.contains("x-content-type-options: nosniff"), "{}", page);
+
+    assert!(head(&server, "/tanka/no-such-tanka").starts_with("http/1.1 404"));
+    assert!(head(&server, "/tanka/no-such-tanka/print").starts_with("http/1.1 404"));
+    assert!(head(&server, "/tag/no-such-tag").starts_with("http/1.1 404"));
+
+    let asset = head(&server, "/style-3f2a1b9c8d7e6f50.css");
+    assert!(asset.contains(&format!("cache-control: {}", CACHE_IMMUTABLE)), "{}", asset);
+    let missing = head(&server, "/style-0000000000000000.css");
+    assert!(missing.starts_with("http/1.1 404") && missing.contains("cache-control: no-cache"), "{}", missing);
+
+    let feed = head(&server, "/rss.xml");
+    assert!(feed.starts_with("http/1.1 200") && feed.contains("application/rss+xml"), "{}", feed);
+
+    // The feed's links would come from the request's Host header otherwise
+    drop(server);
+    let server = start(&dir, None);
+    assert!(head(&server, "/rss.xml").starts_with("http/1.1 404"));
+
+    drop(server);
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,37 @@
+//! Verse links: numbering, reading them back and the quote they copy
+
+mod common;
+
+use common::TankaYaml;
+use digital_khole::share::{quote, verse_id, verse_link, verse_number};
+
+#[test]
+fn verse_links_carry_the_number_for_page_and_server() {
+    assert_eq!(verse_id(3), "v3");
+    assert_eq!(verse_link(3), "?v=3#v3");
+
+    assert_eq!(verse_number("#v3"), Some(3));
+    assert_eq!(verse_number("v3"), Some(3));
+    assert_eq!(verse_number("3"), Some(3));
+    for bad in ["", "#", "#v0", "#v-1", "#verse", "#top"] {
+        assert_eq!(verse_number(bad), None, "{:?}", bad);
+    }
+}
+
+#[test]
+fn verses_count_the_lines_the_page_shows() {
+    let free = TankaYaml::default().with("form", "free-verse").poem("[one, two, \"\", three]").tanka();
+    let text = |n| free.tanka.verse(n).map(|v| v.text.as_str());
+    assert_eq!(text(1), Some("one"));
+    assert_eq!(text(3), Some("three"));
+    assert_eq!(text(0), None);
+    assert_eq!(text(4), None);
+}
+
+#[test]
+fn quotes_name_the_tanka_and_link_back() {
+    assert_eq!(
+        quote(" soul check engine light ", "mom", "https://khole.example/tanka/mom?v=3#v3"),
+        "“soul check engine light” — mom\nhttps://khole.example/tanka/mom?v=3#v3"
+    );
+}
@@ -0,0 +1,61 @@
+//! The in-page shell's commands and tab completion
+
+mod common;
+
+use common::TankaYaml;
+use digital_khole::shell::{complete, run, Output};
+use digital_khole::TankaEntry;
+
+fn entry(name: &str, verse: &str, tanka: TankaYaml) -> TankaEntry {
+    let mut entry = tanka
+        .with("top_flavor", format!("\"tanka // 57577 // [ {} ]\"", name))
+        .with("tanka", format!("{{1: \"{}\", 2: b, 3: c, 4: d, 5: e}}", verse))
+        .entry(&name.replace(' ', "-"));
+    entry.filename = format!("{}.tanka.yml", name);
+    entry
+}
+
+fn book() -> Vec<TankaEntry> {
+    vec![
+        entry("best friend", "puppy making song", TankaYaml::default()),
+        entry("best of", "taken too soon", TankaYaml::default()),
+        entry("null2", "ghost", TankaYaml::default().with("guest_contributor", "{name: 4lung}")),
+    ]
+}
+
+#[test]
+fn commands_work_on_the_book() {
+    let book = book();
+
+    assert_eq!(run("cat best-friend", &book, 0.0), Output::Tanka("best-friend".to_string()));
+    assert_eq!(run("cat best friend.tanka.yml", &book, 0.0), Output::Tanka("best-friend".to_string()));
+    assert_eq!(run("play null2", &book, 0.0), Output::Play("null2".to_string()));
+    assert_eq!(run("random", &book, 0.99), Output::Tanka("null2".to_string()));
+    assert!(matches!(run("cat nope", &book, 0.0), Output::Error(e) if e.contains("No such file")));
+    assert!(matches!(run("sudo rm -rf /", &book, 0.0), Output::Error(e) if e == "sudo: command not found"));
+    assert_eq!(run("  ", &book, 0.0), Output::Nothing);
+
+    let Output::Listing(lines) = run("grep TAKEN", &book, 0.0) else { panic!("no grep matches") };
+    assert_eq!(lines, vec![("best-of:1: taken too soon".to_string(), "best-of".to_string())]);
+
+    let Output::Text(lines) = run("whoami", &book, 0.0) else { panic!() };
+    assert_eq!(lines[1], "4lung - guest contributor (null2)");
+
+    let Output::Listing(lines) = run("tree", &book, 0.0) else { panic!() };
+    assert_eq!(lines.last().unwrap().0, "0 directories, 3 files");
+}
+
+#[test]
+fn tab_completes_commands_and_slugs() {
+    let book = book();
+
+    assert_eq!(complete("wh", &book), ("whoami ".to_string(), vec![]));
+    assert_eq!(complete("cat n", &book), ("cat null2 ".to_string(), vec![]));
+
+    let (line, candidates) = complete("cat b", &book);
+    assert_eq!(line, "cat best-");
+    assert_eq!(candidates, ["best-friend", "best-of"]);
+
+    // grep takes free text, nothing to complete
+    assert_eq!(complete("grep b", &book), ("grep b".to_string(), vec![]));
+}
@@ -0,0 +1,77 @@
+//! Playlist export of the book's music pairings
+
+use digital_khole::soundtrack::{soundtrack, to_jspf, to_m3u, to_xspf};
+mod common;
+
+use common::TankaYaml;
+use digital_khole::{all_tankas, TankaEntry};
+
+fn entry(slug: &str, pairing: &str) -> TankaEntry {
+    TankaYaml::default()
+        .with("qr_link", format!("https://example.bandcamp.com/track/{}", slug))
+        .with("art_link", "null")
+        .pairing(pairing)
+        .entry(slug)
+}
+
+#[test]
+fn every_pairing_in_book_order() {
+    let tankas = [
+        entry("first", "  - {track: one, artist: a, album: b, volume_level: x}\n  - {track: two, artist: a, album: b, volume_level: x, note: louder}\n"),
+        entry("unpaired", "  {track: null, artist: null, album: null, volume_level: null}\n"),
+        entry("last", "  {track: three, artist: c, album: d, volume_level: x, audio: /audio/three.opus}\n"),
+    ];
+
+    let tracks = soundtrack(&tankas, "https://book.example/");
+    let titles: Vec<_> = tracks.iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(titles, ["one", "two", "three"]);
+
+    assert_eq!(tracks[1].annotation, "tanka: first - louder");
+    assert_eq!(tracks[2].info, "https://book.example/tanka/last");
+    assert_eq!(
+        tracks[2].locations,
+        ["https://book.example/audio/three.opus", "https://example.bandcamp.com/track/last"]
+    );
+}
+
+#[test]
+fn formats_escape_and_parse() {
+    let tankas = [entry("amp", "  {track: \"Rock & Roll <3\", artist: \"a\", album: \"b\", volume_level: x}\n")];
+    let tracks = soundtrack(&tankas, "");
+
+    let xspf = to_xspf(&tracks);
+    assert!(xspf.contains("<title>Rock &amp; Roll &lt;3</title>"));
+    assert!(xspf.contains("<annotation>tanka: amp</annotation>"));
+
+    let jspf: serde_json::Value = serde_json::from_str(&to_jspf(&tracks)).unwrap();
+    assert_eq!(jspf["playlist"]["track"][0]["title"], "Rock & Roll <3");
+    assert_eq!(jspf["playlist"]["track"][0]["info"], "/tanka/amp");
+
+    let m3u = to_m3u(&tracks);
+    assert!(m3u.starts_with("#EXTM3U\n"));
+    assert!(m3u.contains("#EXTINF:-1,a - Rock & Roll <3\n#EXTALB:b\nhttps://example.bandcamp.com/track/amp\n"));
+}
+
+#[test]
+fn the_book_has_a_soundtrack() {
+    assert!(!soundtrack(&all_tankas(), "").is_empty());
+}
+
+#[test]
+fn tracks_with_nowhere_to_listen_stay_in_every_format() {
+    let tankas = [entry(
+        "setlist",
+        "  - {track: one, artist: a, album: b, volume_level: x}\n  - {track: two, artist: a, album: b, volume_level: x}\n",
+    )];
+    let tracks = soundtrack(&tankas, "https://book.example");
+    assert!(tracks[1].locations.is_empty());
+
+    let m3u = to_m3u(&tracks);
+    assert!(m3u.contains("#EXTINF:-1,a - one\n#EXTALB:b\nhttps://example.bandcamp.com/track/setlist\n"), "{}", m3u);
+    assert!(
+        m3u.contains("# #EXTINF:-1,a - two\n# #EXTALB:b\n# https://book.example/tanka/setlist\n"),
+        "{}",
+        m3u
+    );
+    assert_eq!(to_xspf(&tracks).matches("<track>").count(), 2);
+}
@@ -0,0 +1,72 @@
+//! Reader data: favorites, reading progress, read marks, settings and moving
+//! them as JSON
+
+use digital_khole::storage::{key, ReaderData, Settings, EXPORT_FORMAT, VERSION};
+
+fn slugs(names: &[&str]) -> Vec<String> {
+    names.iter().map(|n| n.to_string()).collect()
+}
+
+#[test]
+fn keys_are_namespaced_and_versioned() {
+    assert_eq!(key("favorites"), format!("dkh:v{}:favorites", VERSION));
+    assert_eq!(key("last_read"), format!("dkh:v{}:last_read", VERSION));
+}
+
+#[test]
+fn opening_favoriting_and_marking() {
+    let mut reader = ReaderData::default();
+    reader.open("mom");
+    reader.open("null2");
+    reader.open("mom");
+    assert_eq!(reader.read, ["mom", "null2"]);
+    assert_eq!(reader.last_read.as_deref(), Some("mom"));
+    assert_eq!(reader.recently_seen, ["null2", "mom"]);
+
+    reader.set_read("mom", false);
+    assert!(!reader.is_read("mom") && reader.is_read("null2"));
+
+    reader.toggle_favorite("null2");
+    reader.toggle_favorite("mom");
+    assert_eq!(reader.favorites, ["null2", "mom"]);
+    reader.toggle_favorite("null2");
+    assert!(!reader.is_favorite("null2") && reader.is_favorite("mom"));
+
+    reader.retain_known(&slugs(&["null2"]));
+    assert!(reader.favorites.is_empty());
+    assert_eq!(reader.read, ["null2"]);
+    assert_eq!(reader.last_read, None);
+}
+
+#[test]
+fn exports_import_back() {
+    let mut reader = ReaderData::default();
+    reader.open("mom");
+    reader.toggle_favorite("mom");
+    reader.settings = Settings {
+        age_verified: true,
+        cw_mode: Some("custom".to_string()),
+        cw_categories: vec!["grief".to_string()],
+        listening_session: Some(15),
+        theme: Some("templeos".to_string()),
+        locale: Some("ja".to_string()),
+    };
+
+    let json = reader.to_json();
+    assert!(json.contains(&format!("\"format\": \"{}\"", EXPORT_FORMAT)), "{}", json);
+    assert!(json.contains("\"theme\": \"templeos\""), "{}", json);
+    assert_eq!(ReaderData::from_json(&json).unwrap(), reader);
+
+    // Fields left out are empty, so hand-written files work
+    let partial = ReaderData::from_json(r#"{"format": "digital-khole", "version": 1, "reader": {"favorites": ["mom"]}}"#);
+    let partial = partial.unwrap();
+    assert_eq!(partial.favorites, ["mom"]);
+    assert_eq!(partial.settings, Settings::default());
+
+    let newer = json.replace(&format!("\"version\": {}", VERSION), "\"version\": 99");
+    assert!(ReaderData::from_json(&newer).unwrap_err().contains("newer"));
+    assert!(ReaderData::from_json(&json.replace(EXPORT_FORMAT, "something-else")).is_err());
+    assert!(ReaderData::from_json("[]").is_err());
+    assert!(ReaderData::from_json(r#"{"format": "digital-khole", "version": 1, "reader": {"bookmarks": []}}"#).is_err());
+    assert!(ReaderData::from_json(r#"{"format": "digital-khole", "version": 1, "reader": {"settings": {"font": "x"}}}"#).is_err());
+}
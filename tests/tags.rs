@@ -0,0 +1,75 @@
+//! Tags: the vocabulary, listings, the cloud and near-duplicate spellings
+
+mod common;
+
+use common::TankaYaml;
+use digital_khole::all_tankas;
+use digital_khole::tags::{
+    is_well_formed, near_duplicates, parse_vocabulary, suggest, tag, tag_counts, tagged, vocabulary, weight,
+};
+
+#[test]
+fn the_vocabulary_reads_in_file_order() {
+    let tags = parse_vocabulary("grief: loss\ninternet: online\n").unwrap();
+    assert_eq!(tags.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), ["grief", "internet"]);
+    assert_eq!(tags[0].about, "loss");
+    assert_eq!(tags[1].href(), "/tag/internet");
+
+    assert!(parse_vocabulary("# nothing yet\n").unwrap().is_empty());
+    assert!(parse_vocabulary("grief: [loss]\n").is_err());
+    assert!(parse_vocabulary("- grief\n").is_err());
+}
+
+#[test]
+fn every_tag_in_the_book_is_in_the_vocabulary() {
+    let tankas = all_tankas();
+    for entry in &tankas {
+        for name in &entry.tanka.tags {
+            assert!(tag(name).is_some(), "{} is tagged {:?}, which tags.yml doesn't have", entry.slug, name);
+        }
+    }
+
+    let counts = tag_counts(&tankas);
+    assert_eq!(counts.len(), vocabulary().len());
+    for (tag, count) in &counts {
+        assert_eq!(*count, tagged(&tankas, &tag.name).len());
+    }
+}
+
+#[test]
+fn listings_keep_book_order() {
+    let tankas = [
+        TankaYaml::default().with("tags", "[grief, internet]").entry("first"),
+        TankaYaml::default().entry("untagged"),
+        TankaYaml::default().with("tags", "[internet]").entry("last"),
+    ];
+    let slugs = |name| tagged(&tankas, name).iter().map(|t| t.slug.as_str()).collect::<Vec<_>>();
+    assert_eq!(slugs("internet"), ["first", "last"]);
+    assert_eq!(slugs("grief"), ["first"]);
+    assert!(slugs("no-such-tag").is_empty());
+}
+
+#[test]
+fn typos_are_caught_and_suggested() {
+    assert!(is_well_formed("corporate-life"));
+    assert!(!is_well_formed("Grief") && !is_well_formed("two words") && !is_well_formed("a--b") && !is_well_formed(""));
+
+    assert!(near_duplicates("game", "games"));
+    assert!(near_duplicates("childhood", "child-hood"));
+    assert!(near_duplicates("internet", "internt"));
+    assert!(!near_duplicates("work", "word"));
+    assert!(!near_duplicates("grief", "gaming"));
+
+    let vocabulary = parse_vocabulary("family: a\nfriendship: b\n").unwrap();
+    assert_eq!(suggest("famly", &vocabulary), Some("family"));
+    assert_eq!(suggest("Friendships", &vocabulary), Some("friendship"));
+    assert_eq!(suggest("sadness", &vocabulary), None);
+}
+
+#[test]
+fn the_cloud_weighs_tags_by_use() {
+    assert_eq!(weight(0, 0), 1);
+    assert_eq!(weight(0, 8), 1);
+    assert_eq!(weight(1, 8), 2);
+    assert_eq!(weight(8, 8), 5);
+}
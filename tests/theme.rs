@@ -0,0 +1,72 @@
+//! Theme palettes, and that style.css agrees with them
+
+use digital_khole::embed::SITE_PLAYER_COLORS;
+use digital_khole::theme::Theme;
+
+/// The custom properties declared in the first block under `selector`
+fn css_block(selector: &str) -> String {
+    let css = std::fs::read_to_string("style.css").unwrap();
+    let start = css.find(selector).unwrap_or_else(|| panic!("style.css has no {}", selector));
+    let rest = &css[start..];
+    let open = rest.find('{').unwrap();
+    rest[open..rest.find('}').unwrap()].to_string()
+}
+
+/// The block a theme's colors are read from: dark and light share `:root`
+fn theme_block(theme: Theme) -> String {
+    match theme {
+        Theme::Dark | Theme::Light => css_block(":root {"),
+        _ => css_block(&format!(":root[data-theme=\"{}\"]", theme.name())),
+    }
+}
+
+/// `#4d96ff` from a block, with `#fff` spelled out as `ffffff`; of a
+/// `light-dark(#dark, #light)` pair, the side for `theme`
+fn css_color(block: &str, property: &str, theme: Theme) -> String {
+    let decl = format!("{}: ", property);
+    let start = block.find(&decl).unwrap_or_else(|| panic!("no {} in {}", property, block)) + decl.len();
+    let value = &block[start..];
+    let value = match value.strip_prefix("light-dark(") {
+        Some(pair) if theme == Theme::Light => pair.split_once(", ").unwrap().1,
+        Some(pair) => pair,
+        None => value,
+    };
+    let value = value.strip_prefix('#').unwrap_or_else(|| panic!("{} isn't a hex color in {}", property, block));
+    let hex: String = value.chars().take_while(|c| c.is_ascii_hexdigit()).collect();
+    if hex.len() == 3 {
+        hex.chars().flat_map(|c| [c, c]).collect()
+    } else {
+        hex
+    }
+}
+
+#[test]
+fn style_css_matches_palettes() {
+    for theme in Theme::ALL {
+        let block = theme_block(theme);
+        let palette = theme.palette();
+        assert_eq!(css_color(&block, "--bg", theme), palette.background, "{} background", theme);
+        assert_eq!(css_color(&block, "--fg", theme), palette.foreground, "{} foreground", theme);
+        assert_eq!(css_color(&block, "--accent", theme), palette.accent, "{} accent", theme);
+    }
+}
+
+#[test]
+fn qr_codes_stay_dark_on_light() {
+    for theme in Theme::ALL {
+        let (modules, background) = theme.qr_colors();
+        let sum = |c: [u8; 3]| c.iter().map(|&v| u32::from(v)).sum::<u32>();
+        assert!(sum(modules) < sum(background), "{} QR is inverted", theme);
+    }
+    assert_eq!(Theme::Dark.qr_colors(), ([0, 0, 0], [255, 255, 255]));
+}
+
+#[test]
+fn names_round_trip_and_dark_keeps_site_player_colors() {
+    for theme in Theme::ALL {
+        assert_eq!(Theme::from_name(theme.name()), Some(theme));
+    }
+    assert_eq!(Theme::from_name("system"), None);
+    assert_eq!(Theme::Dark.player_colors(), SITE_PLAYER_COLORS);
+    assert_eq!(Theme::TempleOs.player_colors().background, "ffffff");
+}
@@ -0,0 +1,47 @@
+//! volume_level on the five-step scale
+
+use digital_khole::volume::VolumeLevel;
+
+fn parse(yaml: &str) -> Result<Option<VolumeLevel>, String> {
+    serde_yaml::from_str(yaml).map_err(|e| e.to_string())
+}
+
+#[test]
+fn existing_phrasings_land_on_the_scale() {
+    for (text, meter) in [
+        ("comfortable", 3),
+        ("medium", 3),
+        ("medium-high", 4),
+        ("high", 4),
+        ("very high", 5),
+        ("very loud", 5),
+        ("Extremely Loud", 5),
+        ("whisper", 1),
+    ] {
+        let volume = parse(&format!("\"{}\"", text)).unwrap().unwrap();
+        assert_eq!(volume.meter(), Some(meter), "{}", text);
+    }
+
+    assert_eq!(parse("medium").unwrap(), Some(VolumeLevel::Comfortable));
+    assert_eq!(VolumeLevel::Comfortable.label(), "comfortable");
+}
+
+#[test]
+fn custom_wording() {
+    let volume = parse("{ custom: \"YOU'LL GO DEAF\", meter: 5 }").unwrap().unwrap();
+    assert_eq!(volume.label(), "YOU'LL GO DEAF");
+    assert_eq!(volume.player_volume(), Some(1.0));
+    assert_eq!(serde_yaml::to_string(&volume).unwrap(), "custom: YOU'LL GO DEAF\nmeter: 5\n");
+
+    // Unknown plain text is kept, but has no place on the meter
+    let volume = parse("TINNITUS").unwrap().unwrap();
+    assert_eq!(volume.label(), "TINNITUS");
+    assert_eq!(volume.meter(), None);
+}
+
+#[test]
+fn placeholders_and_bad_meters() {
+    assert_eq!(parse("null").unwrap(), None);
+    assert!(parse("{ custom: loud, meter: 6 }").is_err());
+    assert!(parse("{ custom: loud }").is_err());
+}